@@ -0,0 +1,270 @@
+use crate::enums::PayoutRounding;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An amount of chips, kept as a count of half chips so that a 3:2 blackjack
+/// on an odd bet pays exactly.
+///
+/// The operators panic rather than wrap on overflow. Use the `checked_*` methods
+/// for amounts that come from outside the game.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "ChipsValue", into = "ChipsValue")]
+pub struct Chips {
+    half_chips: i64,
+}
+
+// whole amounts are written as plain numbers, so profiles and rules files saved
+// before chips could be split still load, and halves as strings such as "7.5"
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ChipsValue {
+    Whole(i64),
+    Text(String),
+}
+
+impl Chips {
+    pub const ZERO: Chips = Chips { half_chips: 0 };
+
+    pub const fn new(whole_chips: i64) -> Self {
+        match whole_chips.checked_mul(2) {
+            Some(half_chips) => Chips { half_chips },
+            None => panic!("Error: chip amount is too large."),
+        }
+    }
+
+    pub const fn from_half_chips(half_chips: i64) -> Self {
+        Chips { half_chips }
+    }
+
+    pub fn half_chips(&self) -> i64 {
+        self.half_chips
+    }
+
+    pub fn is_whole(&self) -> bool {
+        self.half_chips % 2 == 0
+    }
+
+    pub fn abs(self) -> Self {
+        Chips::from_half_chips(
+            self.half_chips
+                .checked_abs()
+                .expect("Error: chip amount overflowed."),
+        )
+    }
+
+    pub fn checked_add(self, other: Chips) -> Option<Chips> {
+        self.half_chips
+            .checked_add(other.half_chips)
+            .map(Chips::from_half_chips)
+    }
+
+    pub fn checked_sub(self, other: Chips) -> Option<Chips> {
+        self.half_chips
+            .checked_sub(other.half_chips)
+            .map(Chips::from_half_chips)
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Chips> {
+        self.half_chips
+            .checked_mul(factor)
+            .map(Chips::from_half_chips)
+    }
+
+    /// Whether the amount is a whole number of `step`s.
+    pub fn is_multiple_of(self, step: Chips) -> bool {
+        step.half_chips != 0 && self.half_chips % step.half_chips == 0
+    }
+
+    /// The largest whole number of `step`s that is not more than the amount.
+    pub fn round_down_to(self, step: Chips) -> Chips {
+        Chips::from_half_chips(self.half_chips.div_euclid(step.half_chips) * step.half_chips)
+    }
+
+    /// Multiplies by `numerator / denominator`, rounding whatever does not come
+    /// out even the way `rounding` says. Amounts are rounded towards negative infinity.
+    pub fn scale(self, numerator: u32, denominator: u32, rounding: PayoutRounding) -> Chips {
+        let scaled = self.half_chips as i128 * numerator as i128;
+        let denominator = denominator as i128;
+
+        let half_chips = match rounding {
+            PayoutRounding::HalfChip => scaled.div_euclid(denominator),
+            PayoutRounding::Down => scaled.div_euclid(2 * denominator) * 2,
+            PayoutRounding::Nearest => (scaled + denominator).div_euclid(2 * denominator) * 2,
+        };
+
+        Chips::from_half_chips(i64::try_from(half_chips).expect("Error: chip amount overflowed."))
+    }
+}
+
+impl fmt::Display for Chips {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.half_chips < 0 { "-" } else { "" };
+        let half_chips = self.half_chips.unsigned_abs();
+
+        if half_chips.is_multiple_of(2) {
+            write!(f, "{}{}", sign, half_chips / 2)
+        } else {
+            write!(f, "{}{}.5", sign, half_chips / 2)
+        }
+    }
+}
+
+impl FromStr for Chips {
+    type Err = String;
+
+    /// Parses whole amounts such as `25` and half amounts such as `12.5`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid chip amount '{}'", value);
+
+        let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), "0"));
+        let is_negative = whole.starts_with('-');
+        let whole_chips = whole.parse::<i64>().map_err(|_| invalid())?;
+        let half_chip = match fraction {
+            "0" => 0,
+            "5" if is_negative => -1,
+            "5" => 1,
+            _ => return Err(invalid()),
+        };
+
+        whole_chips
+            .checked_mul(2)
+            .and_then(|half_chips| half_chips.checked_add(half_chip))
+            .map(Chips::from_half_chips)
+            .ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<ChipsValue> for Chips {
+    type Error = String;
+
+    fn try_from(value: ChipsValue) -> Result<Self, Self::Error> {
+        match value {
+            ChipsValue::Whole(whole_chips) => whole_chips
+                .checked_mul(2)
+                .map(Chips::from_half_chips)
+                .ok_or_else(|| format!("chip amount {} is too large", whole_chips)),
+            ChipsValue::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<Chips> for ChipsValue {
+    fn from(value: Chips) -> Self {
+        if value.is_whole() {
+            ChipsValue::Whole(value.half_chips / 2)
+        } else {
+            ChipsValue::Text(value.to_string())
+        }
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+
+    fn add(self, other: Chips) -> Chips {
+        self.checked_add(other)
+            .expect("Error: chip amount overflowed.")
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Chips) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+
+    fn sub(self, other: Chips) -> Chips {
+        self.checked_sub(other)
+            .expect("Error: chip amount overflowed.")
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Chips) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Chips {
+    type Output = Chips;
+
+    fn mul(self, factor: i64) -> Chips {
+        self.checked_mul(factor)
+            .expect("Error: chip amount overflowed.")
+    }
+}
+
+impl Neg for Chips {
+    type Output = Chips;
+
+    fn neg(self) -> Chips {
+        Chips::from_half_chips(
+            self.half_chips
+                .checked_neg()
+                .expect("Error: chip amount overflowed."),
+        )
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(iter: I) -> Chips {
+        iter.fold(Chips::ZERO, |total, chips| total + chips)
+    }
+}
+
+#[test]
+fn test_chips_arithmetic_and_persistence() {
+    use crate::models::PlayerProfile;
+
+    let bet = Chips::new(5);
+    assert_eq!(bet.scale(3, 2, PayoutRounding::HalfChip).to_string(), "7.5");
+    assert_eq!(bet.scale(3, 2, PayoutRounding::Down), Chips::new(7));
+    assert_eq!(bet.scale(3, 2, PayoutRounding::Nearest), Chips::new(8));
+    assert_eq!(
+        Chips::new(3)
+            .scale(6, 5, PayoutRounding::HalfChip)
+            .to_string(),
+        "3.5"
+    );
+    assert_eq!(
+        Chips::new(3).scale(6, 5, PayoutRounding::Nearest),
+        Chips::new(4)
+    );
+    assert_eq!(
+        Chips::new(10).scale(6, 5, PayoutRounding::Down),
+        Chips::new(12)
+    );
+
+    assert_eq!("12.5".parse::<Chips>(), Ok(Chips::from_half_chips(25)));
+    assert_eq!("-0.5".parse::<Chips>(), Ok(Chips::from_half_chips(-1)));
+    assert_eq!((-Chips::from_half_chips(7)).to_string(), "-3.5");
+    assert!("12.25".parse::<Chips>().is_err());
+    assert!("h".parse::<Chips>().is_err());
+
+    assert_eq!(
+        Chips::from_half_chips(i64::MAX).checked_add(Chips::new(1)),
+        None
+    );
+    assert_eq!(Chips::new(1).checked_mul(i64::MAX), None);
+
+    // whole balances are saved the way they always were, halves survive the round trip
+    let profile: PlayerProfile = serde_json::from_str(r#"{"balance":500}"#).unwrap();
+    assert_eq!(profile.balance, Chips::new(500));
+    let profile = PlayerProfile {
+        balance: Chips::from_half_chips(1015),
+        side_bets: Default::default(),
+    };
+    let saved_profile = serde_json::to_string(&profile).unwrap();
+    assert_eq!(saved_profile, r#"{"balance":"507.5"}"#);
+    let loaded_profile: PlayerProfile = serde_json::from_str(&saved_profile).unwrap();
+    assert_eq!(loaded_profile.balance, profile.balance);
+}
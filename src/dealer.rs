@@ -0,0 +1,121 @@
+use crate::enums::DealerStep;
+use crate::hand::Hand;
+use crate::models::Card;
+use crate::rules::HouseRules;
+
+/// Whether the dealer draws to `dealer_hand`: always below 17, and on a soft 17
+/// only when the table hits soft 17 (H17) rather than standing on all 17s (S17).
+pub fn is_dealer_hitting(rules: &HouseRules, dealer_hand: &Hand) -> bool {
+    let dealer_hand_sum = dealer_hand.soft_total();
+
+    dealer_hand_sum < 17
+        || (dealer_hand_sum == 17 && dealer_hand.is_soft() && rules.dealer_hits_soft_17)
+}
+
+/// Plays the dealer's hand out, drawing each card from `draw`, and returns
+/// whether the dealer stayed or busted.
+pub fn dealer_play(
+    rules: &HouseRules,
+    dealer_hand: &mut Hand,
+    mut draw: impl FnMut() -> Card,
+) -> DealerStep {
+    loop {
+        match dealer_play_step(rules, dealer_hand, &mut draw) {
+            DealerStep::Hit => continue,
+            dealer_step => return dealer_step,
+        }
+    }
+}
+
+/// Plays a single dealer decision on `dealer_hand`, drawing from `draw` only when
+/// the dealer hits.
+pub fn dealer_play_step(
+    rules: &HouseRules,
+    dealer_hand: &mut Hand,
+    draw: impl FnOnce() -> Card,
+) -> DealerStep {
+    if !is_dealer_hitting(rules, dealer_hand) {
+        return DealerStep::Stay;
+    }
+
+    dealer_hand.push(draw());
+    if dealer_hand.is_bust() {
+        DealerStep::Bust
+    } else {
+        DealerStep::Hit
+    }
+}
+
+#[test]
+fn test_dealer_play() {
+    use crate::enums::{Rank, Suit};
+
+    let card = |rank: Rank| Card::new(rank, Suit::Spades);
+    let stand_rules = HouseRules::default();
+    let hit_rules = HouseRules {
+        dealer_hits_soft_17: true,
+        ..HouseRules::default()
+    };
+
+    // soft 17 is the only hand the two rules play differently
+    let soft_17 = Hand::from_cards(vec![card(Rank::Ace), card(Rank::Six)]);
+    assert!(!is_dealer_hitting(&stand_rules, &soft_17));
+    assert!(is_dealer_hitting(&hit_rules, &soft_17));
+    let hard_17 = Hand::from_cards(vec![card(Rank::Ten), card(Rank::Seven)]);
+    assert!(!is_dealer_hitting(&hit_rules, &hard_17));
+    let soft_17_of_three =
+        Hand::from_cards(vec![card(Rank::Ace), card(Rank::Two), card(Rank::Four)]);
+    assert!(is_dealer_hitting(&hit_rules, &soft_17_of_three));
+    let hard_17_with_ace =
+        Hand::from_cards(vec![card(Rank::Ace), card(Rank::Six), card(Rank::Ten)]);
+    assert!(!is_dealer_hitting(&hit_rules, &hard_17_with_ace));
+
+    let mut hand = soft_17.clone();
+    assert_eq!(
+        dealer_play(&stand_rules, &mut hand, || card(Rank::Five)),
+        DealerStep::Stay
+    );
+    assert_eq!(hand.len(), 2);
+    let mut hand = soft_17.clone();
+    assert_eq!(
+        dealer_play(&hit_rules, &mut hand, || card(Rank::Five)),
+        DealerStep::Stay
+    );
+    // A 6 5 is a hard 12, so the dealer draws again to a hard 17
+    assert_eq!(hand.len(), 4);
+    assert_eq!(hand.soft_total(), 17);
+
+    // every starting pair of card values, drawing the same card each time
+    for first in Rank::ALL {
+        for second in Rank::ALL {
+            for draw_rank in Rank::ALL {
+                for rules in [&stand_rules, &hit_rules] {
+                    let mut hand = Hand::from_cards(vec![card(first), card(second)]);
+                    let dealer_step = dealer_play(rules, &mut hand, || card(draw_rank));
+
+                    assert!(!is_dealer_hitting(rules, &hand));
+
+                    // the round plays the same hand out one step at a time
+                    let mut stepped_hand = Hand::from_cards(vec![card(first), card(second)]);
+                    let mut stepped_dealer_step =
+                        dealer_play_step(rules, &mut stepped_hand, || card(draw_rank));
+                    while stepped_dealer_step == DealerStep::Hit {
+                        stepped_dealer_step =
+                            dealer_play_step(rules, &mut stepped_hand, || card(draw_rank));
+                    }
+                    assert_eq!(stepped_dealer_step, dealer_step);
+                    assert_eq!(stepped_hand, hand);
+                    assert_eq!(dealer_step == DealerStep::Bust, hand.is_bust());
+                    if dealer_step == DealerStep::Stay {
+                        assert!((17..=21).contains(&hand.soft_total()));
+                        assert!(
+                            !(rules.dealer_hits_soft_17
+                                && hand.soft_total() == 17
+                                && hand.is_soft())
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::enums::{Rank, Suit};
+use crate::models::Card;
+use crate::rules::HouseRules;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub const SHOE_DECK_COUNTS: [u8; 4] = [1, 2, 6, 8];
+
+/// The cards in play at a table, dealt from one or more shuffled decks.
+///
+/// A cut card is placed at `penetration_percent` of the way into the shoe and
+/// once it has been dealt past, the shoe should be reshuffled before the next round.
+///
+/// Shuffles come from the shoe's own RNG, so two shoes built from the same seed
+/// deal the same cards for the same sequence of draws.
+pub struct Shoe {
+    cards: Vec<Card>,
+    rng: Box<dyn RngCore>,
+    seed: Option<u64>,
+    deck_count: u8,
+    removed_ranks: Vec<Rank>,
+    penetration_percent: u8,
+    cut_card_position: usize,
+}
+
+impl Shoe {
+    /// Builds a shoe from a freshly chosen seed, which is kept so the session can be replayed.
+    pub fn new(deck_count: u8, penetration_percent: u8) -> Self {
+        Shoe::seeded(deck_count, penetration_percent, rand::thread_rng().gen())
+    }
+
+    pub fn seeded(deck_count: u8, penetration_percent: u8, seed: u64) -> Self {
+        let mut shoe = Shoe::with_rng(
+            deck_count,
+            penetration_percent,
+            Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        );
+        shoe.seed = Some(seed);
+        shoe
+    }
+
+    /// Builds the shoe a table's rules call for from `seed`.
+    pub fn seeded_for_rules(rules: &HouseRules, seed: u64) -> Self {
+        Shoe::seeded(rules.deck_count, rules.penetration_percent, seed)
+            .without_ranks(&rules.removed_ranks)
+    }
+
+    /// Takes every card of `ranks` out of the decks, such as the tens for Spanish 21,
+    /// and reshuffles.
+    pub fn without_ranks(mut self, ranks: &[Rank]) -> Self {
+        if !ranks.is_empty() {
+            self.removed_ranks = ranks.to_vec();
+            self.shuffle();
+        }
+        self
+    }
+
+    pub fn with_rng(deck_count: u8, penetration_percent: u8, rng: Box<dyn RngCore>) -> Self {
+        assert!(
+            SHOE_DECK_COUNTS.contains(&deck_count),
+            "Error: a shoe must hold 1, 2, 6 or 8 decks."
+        );
+        assert!(
+            (1..=100).contains(&penetration_percent),
+            "Error: shoe penetration must be between 1 and 100 percent."
+        );
+
+        let mut shoe = Shoe {
+            cards: vec![],
+            rng,
+            seed: None,
+            deck_count,
+            removed_ranks: vec![],
+            penetration_percent,
+            cut_card_position: 0,
+        };
+        shoe.shuffle();
+        shoe
+    }
+
+    /// Builds a shoe that deals `cards` in order from the back, without shuffling or burning.
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Shoe {
+            cards,
+            rng: Box::new(ChaCha8Rng::seed_from_u64(0)),
+            seed: None,
+            deck_count: 1,
+            removed_ranks: vec![],
+            penetration_percent: 100,
+            cut_card_position: 0,
+        }
+    }
+
+    /// Builds a shoe like `from_cards` of one card of `suit` for each of `ranks`.
+    pub fn from_ranks(ranks: &[Rank], suit: Suit) -> Self {
+        Shoe::from_cards(ranks.iter().map(|&rank| Card::new(rank, suit)).collect())
+    }
+
+    /// The seed the shoe was built from, if it was not given its own RNG.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn deck_count(&self) -> u8 {
+        self.deck_count
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_cut_card_reached(&self) -> bool {
+        self.cards.len() <= self.cut_card_position
+    }
+
+    /// Collects every card back into the shoe, shuffles, places the cut card and burns the top card.
+    pub fn shuffle(&mut self) {
+        self.cards.clear();
+        for _ in 0..self.deck_count {
+            for suit in Suit::ALL {
+                for rank in Rank::ALL {
+                    if !self.removed_ranks.contains(&rank) {
+                        self.cards.push(Card::new(rank, suit));
+                    }
+                }
+            }
+        }
+
+        self.cards.shuffle(&mut self.rng);
+
+        // cards are dealt from the back so the cut card sits this many cards from the front
+        let total_cards = self.cards.len();
+        self.cut_card_position =
+            total_cards - total_cards * self.penetration_percent as usize / 100;
+
+        self.cards.pop();
+    }
+
+    pub fn draw(&mut self) -> Card {
+        // the cut card should stop this happening but never let a round run dry mid hand
+        if self.cards.is_empty() {
+            self.shuffle();
+        }
+
+        self.cards
+            .pop()
+            .expect("Error: shoe is empty after shuffling.")
+    }
+}
+
+#[test]
+fn test_shoe_penetration_and_burn() {
+    let mut shoe = Shoe::new(6, 75);
+
+    // one card is burned after every shuffle
+    assert_eq!(shoe.cards_remaining(), 6 * 52 - 1);
+    assert!(!shoe.is_cut_card_reached());
+
+    let mut cards_dealt = 0;
+    while !shoe.is_cut_card_reached() {
+        shoe.draw();
+        cards_dealt += 1;
+    }
+    assert_eq!(cards_dealt, 6 * 52 * 3 / 4 - 1);
+
+    shoe.shuffle();
+    assert_eq!(shoe.cards_remaining(), 6 * 52 - 1);
+    assert!(!shoe.is_cut_card_reached());
+}
+
+#[test]
+fn test_seeded_shoes_deal_the_same_cards() {
+    let mut first_shoe = Shoe::seeded(2, 50, 42);
+    let mut second_shoe = Shoe::seeded(2, 50, 42);
+    assert_eq!(first_shoe.seed(), Some(42));
+
+    // deal through a reshuffle to make sure the RNG state carries over between shuffles
+    for _ in 0..200 {
+        if first_shoe.is_cut_card_reached() {
+            first_shoe.shuffle();
+            second_shoe.shuffle();
+        }
+        assert_eq!(first_shoe.draw(), second_shoe.draw());
+    }
+
+    let mut other_shoe = Shoe::seeded(2, 50, 43);
+    let mut first_shoe = Shoe::seeded(2, 50, 42);
+    let first_cards: Vec<Card> = (0..10).map(|_| first_shoe.draw()).collect();
+    let other_cards: Vec<Card> = (0..10).map(|_| other_shoe.draw()).collect();
+    assert_ne!(first_cards, other_cards);
+}
+
+#[test]
+fn test_shoe_without_ranks() {
+    use crate::enums::GameVariant;
+
+    let rules = HouseRules::for_variant(GameVariant::Spanish21);
+    let mut shoe = Shoe::seeded_for_rules(&rules, 42);
+    assert_eq!(shoe.cards_remaining(), 6 * 48 - 1);
+    for _ in 0..100 {
+        assert_ne!(shoe.draw().rank, Rank::Ten);
+    }
+    // the tens stay out after a reshuffle
+    shoe.shuffle();
+    assert_eq!(shoe.cards_remaining(), 6 * 48 - 1);
+
+    // a shoe with every rank taken out would have nothing to deal
+    let empty_rules = HouseRules {
+        removed_ranks: Rank::ALL.to_vec(),
+        ..HouseRules::default()
+    };
+    assert!(empty_rules.validate().is_err());
+}
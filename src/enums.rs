@@ -1,7 +1,461 @@
-#[derive(Debug, PartialEq)]
+use crate::chips::Chips;
+use crate::models::{Card, TableSnapshot};
+use crate::rules::{HouseRules, PayoutRatio};
+use crate::side_bets::SideBetResult;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PlayerAction {
     Hit,
     Stay,
     DoubleDown,
+    // doubling with less than the bet, up to the bet
+    DoubleForLess(Chips),
     Split,
+    Insurance,
+    DeclineInsurance,
+    EvenMoney,
+    Surrender,
+    // taking back a double that has not busted, losing only the original bet
+    Rescue,
+    // swapping the second cards of the two starting hands
+    Switch,
+    // pontoon's hit, stay and its paid card, which raises the bet by the stake
+    // without ending the hand
+    Twist,
+    Stick,
+    Buy,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundState {
+    PlayerTurn { hand_index: usize },
+    DealerTurn,
+    Complete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DealerStep {
+    Hit,
+    Bust,
+    Stay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "seat", rename_all = "snake_case")]
+pub enum Seat {
+    Dealer,
+    Player { hand_index: usize },
+}
+
+/// Everything a round reports as it is played, in the order it happens.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    // the rules in play, once as a player sits down
+    TableOpened {
+        rules: HouseRules,
+    },
+    ShoeShuffled,
+    RoundStarted {
+        balance: Chips,
+        bet: Chips,
+    },
+    // card is None when dealt face down
+    CardDealt {
+        seat: Seat,
+        card: Option<Card>,
+    },
+    Blackjack {
+        hand_index: usize,
+        player: bool,
+        dealer: bool,
+    },
+    TableShown {
+        table: TableSnapshot,
+    },
+    // cost is what insurance takes from the balance, up to half the bet
+    InsuranceOffered {
+        cost: Chips,
+        is_even_money: bool,
+    },
+    SideBetSettled {
+        result: SideBetResult,
+    },
+    InsuranceSettled {
+        payout: Chips,
+    },
+    DecisionRequired {
+        hand_index: usize,
+        available_actions: Vec<PlayerAction>,
+    },
+    ActionTaken {
+        hand_index: usize,
+        action: PlayerAction,
+        bet: Chips,
+        free_bet: Chips,
+    },
+    HandBusted {
+        hand_index: usize,
+    },
+    HandRuleApplied {
+        hand_index: usize,
+        rule: HandRule,
+        payout: Chips,
+    },
+    // hole_card is None when the rules deal no hole card
+    DealerTurnStarted {
+        hole_card: Option<Card>,
+    },
+    DealerActed {
+        step: DealerStep,
+    },
+    HandsCompared {
+        dealer_total: u8,
+        player_totals: Vec<(usize, u8)>,
+        hand_count: usize,
+    },
+    HandSettled {
+        hand_index: usize,
+        payout: Chips,
+    },
+    RoundSettled {
+        total_payout: Chips,
+        hand_count: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundError {
+    ActionNotAvailable(PlayerAction),
+    NotPlayerTurn,
+    NotDealerTurn,
+}
+
+impl fmt::Display for RoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoundError::ActionNotAvailable(action) => {
+                write!(f, "{:?} is not available for the active hand", action)
+            }
+            RoundError::NotPlayerTurn => write!(f, "it is not the player's turn"),
+            RoundError::NotDealerTurn => write!(f, "it is not the dealer's turn"),
+        }
+    }
+}
+
+impl std::error::Error for RoundError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameError {
+    InputEnded,
+    InvalidBet(Chips),
+    Round(RoundError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::InputEnded => write!(f, "the player input ended in the middle of a round"),
+            GameError::InvalidBet(bet) => {
+                write!(f, "a bet of {} is not allowed at this table", bet)
+            }
+            GameError::Round(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(String, std::io::Error),
+    InvalidLine { line_number: usize, line: String },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Io(path, err) => write!(f, "failed to read script {}: {}", path, err),
+            ScriptError::InvalidLine { line_number, line } => write!(
+                f,
+                "script line {} '{}' is not a bet, (e)xit or action letter",
+                line_number, line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(String, std::io::Error),
+    Parse(String),
+    Rules(RulesError),
+    InvalidAction { round: usize, error: RoundError },
+    MissingActions { round: usize },
+    UnusedActions { round: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(path, err) => {
+                write!(f, "failed to access session record {}: {}", path, err)
+            }
+            ReplayError::Parse(err) => write!(f, "failed to parse session record: {}", err),
+            ReplayError::Rules(err) => write!(f, "session record has {}", err),
+            ReplayError::InvalidAction { round, error } => {
+                write!(f, "round {} could not be replayed: {}", round, error)
+            }
+            ReplayError::MissingActions { round } => {
+                write!(f, "round {} ran out of recorded actions", round)
+            }
+            ReplayError::UnusedActions { round } => {
+                write!(
+                    f,
+                    "round {} finished before all recorded actions were used",
+                    round
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    // 1(or 11) 2, 3, 4, 5, 6, 7, 8, 9, 10, J(10), Q(10), K(10)
+    pub fn numeric_value(&self) -> u8 {
+        match self {
+            Rank::Ace => 1,
+            Rank::Two => 2,
+            Rank::Three => 3,
+            Rank::Four => 4,
+            Rank::Five => 5,
+            Rank::Six => 6,
+            Rank::Seven => 7,
+            Rank::Eight => 8,
+            Rank::Nine => 9,
+            Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        }
+    }
+
+    pub fn face_value(&self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Spades => '♠',
+        }
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Surrender {
+    None,
+    Late,
+    Early,
+}
+
+/// A hand shape that settles a hand the moment it is made, without waiting on the dealer.
+///
+/// A table checks its hand rules in the order they are listed and applies the first match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum HandRule {
+    // a hand of this many cards that has not busted wins even money
+    CardCharlie {
+        cards: u8,
+    },
+    // a hand of this many cards that has not busted wins the payout, as pontoon's
+    // five card trick
+    CardTrick {
+        cards: u8,
+        payout: PayoutRatio,
+    },
+    // any 21 wins even money, whatever the dealer ends up with
+    TwentyOneWins,
+    // a 21 of at least this many cards pays the bonus, unless the hand doubled
+    CardCountTwentyOne {
+        cards: u8,
+        payout: PayoutRatio,
+    },
+    // a three card 21 of these ranks pays the bonus, more when suited or all spades,
+    // unless the hand doubled
+    ThreeCardTwentyOne {
+        ranks: [Rank; 3],
+        mixed: PayoutRatio,
+        suited: PayoutRatio,
+        spades: PayoutRatio,
+    },
+}
+
+/// The games the table can be opened as, each a preset of house rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameVariant {
+    Classic,
+    // the tens are taken out of the shoe, and 21s and double downs pay the player back
+    Spanish21,
+    // two hands whose second cards may be swapped, paid for by a dealer 22 pushing
+    BlackjackSwitch,
+    // both dealer cards are dealt face up, paid for by the dealer winning ties
+    DoubleExposure,
+    // the house puts up doubles on 9 to 11 and splits of anything but tens,
+    // paid for by a dealer 22 pushing
+    FreeBet,
+    // the British game, with twists, sticks and bought cards and five card tricks
+    Pontoon,
+}
+
+impl GameVariant {
+    pub const ALL: [GameVariant; 6] = [
+        GameVariant::Classic,
+        GameVariant::Spanish21,
+        GameVariant::BlackjackSwitch,
+        GameVariant::DoubleExposure,
+        GameVariant::FreeBet,
+        GameVariant::Pontoon,
+    ];
+
+    /// The name the variant is chosen by on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameVariant::Classic => "classic",
+            GameVariant::Spanish21 => "spanish21",
+            GameVariant::BlackjackSwitch => "switch",
+            GameVariant::DoubleExposure => "double_exposure",
+            GameVariant::FreeBet => "free_bet",
+            GameVariant::Pontoon => "pontoon",
+        }
+    }
+}
+
+/// Which hands may double down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoubleRule {
+    AnyCards,
+    AnyTwoCards,
+    HardNineToEleven,
+    HardTenToEleven,
+}
+
+/// Whether the dealer takes a hole card and peeks for blackjack, or takes their
+/// second card only after the player has acted, as at European tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoleCardRule {
+    Peek,
+    // every bet, doubles and splits included, is lost to a dealer blackjack
+    NoHoleCard,
+    // only the original bet is lost to a dealer blackjack
+    NoHoleCardOriginalBetOnly,
+}
+
+/// How a payout that does not come to a whole number of half chips is paid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutRounding {
+    // pay half chips, rounding anything smaller down
+    HalfChip,
+    // pay whole chips only, rounding down
+    Down,
+    // pay whole chips only, rounding halves up
+    Nearest,
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(String, std::io::Error),
+    Parse(String),
+    UnsupportedFormat(String),
+    Invalid(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Io(path, err) => write!(f, "failed to read rules file {}: {}", path, err),
+            RulesError::Parse(err) => write!(f, "failed to parse rules file: {}", err),
+            RulesError::UnsupportedFormat(path) => {
+                write!(f, "rules file {} must end in .json or .toml", path)
+            }
+            RulesError::Invalid(reason) => write!(f, "invalid house rules: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
@@ -0,0 +1,167 @@
+use crate::chips::Chips;
+use crate::deck::Shoe;
+use crate::enums::{GameError, GameEvent, RoundState};
+use crate::input::PlayerInput;
+use crate::render::GameRenderer;
+use crate::replay::SessionRecord;
+use crate::round::Round;
+use crate::rules::HouseRules;
+
+pub struct SessionOutcome {
+    pub record: SessionRecord,
+    pub final_balance: Chips,
+}
+
+/// Plays a whole round, asking `input` for every decision and passing each event
+/// to `renderer`, and returns the round once complete.
+pub fn play_round<'a>(
+    shoe: &'a mut Shoe,
+    rules: &'a HouseRules,
+    balance: Chips,
+    bet: Chips,
+    side_bets: &[Chips],
+    input: &mut dyn PlayerInput,
+    renderer: &mut dyn GameRenderer,
+) -> Result<Round<'a>, GameError> {
+    let mut round = Round::deal_with_side_bets(shoe, rules, balance, bet, side_bets);
+    render_events(&mut round, renderer);
+
+    while let RoundState::PlayerTurn { hand_index } = round.state() {
+        let hand = &round.player().hands[hand_index];
+        let action = input
+            .choose_action(hand, round.dealer_upcard(), &hand.avaiable_actions, rules)
+            .ok_or(GameError::InputEnded)?;
+        round.apply_action(action).map_err(GameError::Round)?;
+        render_events(&mut round, renderer);
+    }
+
+    while round.state() == RoundState::DealerTurn {
+        round.dealer_step().map_err(GameError::Round)?;
+        render_events(&mut round, renderer);
+    }
+
+    Ok(round)
+}
+
+/// Checks a bet and its side bets against the table limits and `balance` before
+/// they are played, as input such as a script is not held to them.
+pub fn check_bets(
+    rules: &HouseRules,
+    balance: Chips,
+    bet: Chips,
+    side_bets: &[Chips],
+) -> Result<(), GameError> {
+    if !rules.is_bet_allowed(balance, bet) {
+        return Err(GameError::InvalidBet(bet));
+    }
+    if !rules.are_side_bets_allowed(balance, bet, side_bets) {
+        return Err(GameError::InvalidBet(side_bets.iter().copied().sum()));
+    }
+    Ok(())
+}
+
+/// Shows the table rules to the player before the first round.
+pub fn open_table(rules: &HouseRules, renderer: &mut dyn GameRenderer) {
+    renderer.render(&GameEvent::TableOpened {
+        rules: rules.clone(),
+    });
+}
+
+fn render_events(round: &mut Round, renderer: &mut dyn GameRenderer) {
+    for event in round.take_events() {
+        renderer.render(&event);
+    }
+}
+
+/// Plays rounds from a freshly seeded shoe until `input` leaves the table or the
+/// balance no longer covers the table minimum on every hand dealt. After each round
+/// `on_round_settled` sees the round, the new balance and the session recorded so far.
+pub fn play_session(
+    rules: &HouseRules,
+    seed: u64,
+    starting_balance: Chips,
+    input: &mut dyn PlayerInput,
+    renderer: &mut dyn GameRenderer,
+    on_round_settled: &mut dyn FnMut(&Round, Chips, &SessionRecord),
+) -> Result<SessionOutcome, GameError> {
+    let mut shoe = Shoe::seeded_for_rules(rules, seed);
+    let mut record = SessionRecord::new(seed, rules.clone(), starting_balance);
+    let mut balance = starting_balance;
+    open_table(rules, renderer);
+
+    while balance >= rules.min_round_stake() {
+        let bet = match input.choose_bet(balance, rules) {
+            Some(bet) => bet,
+            None => break,
+        };
+        let side_bets = input.choose_side_bets(balance, bet, rules);
+        check_bets(rules, balance, bet, &side_bets)?;
+
+        let round = play_round(&mut shoe, rules, balance, bet, &side_bets, input, renderer)?;
+        balance += round
+            .total_payout()
+            .expect("Error: round finished without a payout.");
+        record.record_round(bet, &round);
+        on_round_settled(&round, balance, &record);
+    }
+
+    Ok(SessionOutcome {
+        record,
+        final_balance: balance,
+    })
+}
+
+#[test]
+fn test_bot_session_replays() {
+    use crate::input::{mimic_dealer_strategy, BotInput, ScriptedInput};
+    use crate::render::SilentRenderer;
+
+    let rules = HouseRules::default();
+    let mut bot_input = BotInput::new(Chips::new(10), 50, mimic_dealer_strategy);
+    let mut settled_balances = Vec::new();
+    let outcome = play_session(
+        &rules,
+        11,
+        Chips::new(500),
+        &mut bot_input,
+        &mut SilentRenderer,
+        &mut |_, balance, _| settled_balances.push(balance),
+    )
+    .unwrap();
+    assert_eq!(outcome.record.rounds.len(), 50);
+    assert_eq!(settled_balances.len(), 50);
+    assert_eq!(settled_balances.last(), Some(&outcome.final_balance));
+
+    // a script of the bot's decisions drives the same session to the same balance
+    let mut scripted_input = ScriptedInput::from_session_record(&outcome.record);
+    let scripted_outcome = play_session(
+        &rules,
+        11,
+        Chips::new(500),
+        &mut scripted_input,
+        &mut SilentRenderer,
+        &mut |_, _, _| {},
+    )
+    .unwrap();
+    assert_eq!(scripted_outcome.record, outcome.record);
+    assert_eq!(scripted_outcome.final_balance, outcome.final_balance);
+
+    let replayed_rounds = outcome.record.replay().unwrap();
+    assert_eq!(
+        replayed_rounds.last().unwrap().balance_after,
+        outcome.final_balance
+    );
+
+    let mut greedy_input = ScriptedInput::new(vec![Chips::new(501)], vec![]);
+    assert!(matches!(
+        play_session(
+            &rules,
+            11,
+            Chips::new(500),
+            &mut greedy_input,
+            &mut SilentRenderer,
+            &mut |_, _, _| {},
+        ),
+        Err(GameError::InvalidBet(bet)) if bet == Chips::new(501)
+    ));
+}
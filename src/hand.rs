@@ -0,0 +1,145 @@
+use crate::models::Card;
+
+/// The cards held by the dealer or by one of the player's hands.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn new() -> Self {
+        Hand { cards: vec![] }
+    }
+
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Hand { cards }
+    }
+
+    pub fn push(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn card_values(&self) -> Vec<u8> {
+        self.cards.iter().map(Card::numeric_value).collect()
+    }
+
+    /// The total counting every ace as 1.
+    pub fn hard_total(&self) -> u8 {
+        self.card_values().iter().sum()
+    }
+
+    /// The best total, counting aces as 11 wherever that does not bust the hand.
+    pub fn soft_total(&self) -> u8 {
+        get_hand_sum_legacy(&self.card_values())
+    }
+
+    /// Whether an ace is currently being counted as 11.
+    pub fn is_soft(&self) -> bool {
+        self.soft_total() != self.hard_total()
+    }
+
+    pub fn is_blackjack(&self) -> bool {
+        self.cards.len() == 2 && self.soft_total() == 21
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.hard_total() > 21
+    }
+
+    /// Two cards of the same value, so K-Q counts as a pair.
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].numeric_value() == self.cards[1].numeric_value()
+    }
+
+    /// Two cards of the same rank, so K-K counts as a pair but K-Q does not.
+    pub fn is_rank_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].rank == self.cards[1].rank
+    }
+}
+
+pub fn get_hand_sum_legacy(cards: &[u8]) -> u8 {
+    let min_sum: u8 = cards.iter().sum();
+
+    let number_of_aces = cards.iter().filter(|&&x| x == 1_u8).count() as u8;
+
+    let max_ace_10_padding = (21_u8.saturating_sub(min_sum)) / 10_u8; // max amount of 10s we can add without going over 21
+
+    // compare what aces we have to the ideal amount of padding to be added
+    // make sure we add the best amount we can considering how much aces we have
+    let ace_adjustment = std::cmp::min(number_of_aces, max_ace_10_padding);
+
+    #[allow(clippy::let_and_return)]
+    let hand_value = min_sum + (ace_adjustment * 10);
+
+    hand_value
+}
+
+#[test]
+fn test_get_hand_sum() {
+    assert_eq!(get_hand_sum_legacy(&[10, 10]), 20);
+    assert_eq!(get_hand_sum_legacy(&[10, 10, 10]), 30);
+    assert_eq!(get_hand_sum_legacy(&[5, 6]), 11);
+    assert_eq!(get_hand_sum_legacy(&[6, 10]), 16);
+    assert_eq!(get_hand_sum_legacy(&[1, 10]), 21);
+    assert_eq!(get_hand_sum_legacy(&[4, 5]), 9);
+    assert_eq!(get_hand_sum_legacy(&[4, 5, 1]), 20);
+    assert_eq!(get_hand_sum_legacy(&[4, 5, 1, 1]), 21);
+    assert_eq!(get_hand_sum_legacy(&[4, 5, 1, 1, 1]), 12);
+    assert_eq!(get_hand_sum_legacy(&[10, 10, 1]), 21);
+    assert_eq!(get_hand_sum_legacy(&[10, 8]), 18);
+    assert_eq!(get_hand_sum_legacy(&[10, 8, 1]), 19);
+    assert_eq!(get_hand_sum_legacy(&[10, 8, 1, 1]), 20);
+    assert_eq!(get_hand_sum_legacy(&[]), 0);
+    assert_eq!(get_hand_sum_legacy(&[1]), 11);
+    assert_eq!(get_hand_sum_legacy(&[1, 1]), 12);
+    assert_eq!(get_hand_sum_legacy(&[1, 1, 1, 1, 1, 1, 1, 1, 1]), 19);
+    assert_eq!(get_hand_sum_legacy(&[1, 1, 1, 1, 1, 1, 1, 1, 1, 1]), 20);
+    assert_eq!(get_hand_sum_legacy(&[1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]), 21);
+    assert_eq!(
+        get_hand_sum_legacy(&[1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]),
+        12
+    );
+    assert_eq!(get_hand_sum_legacy(&[4]), 4);
+}
+
+#[test]
+fn test_hand_evaluation() {
+    use crate::enums::{Rank, Suit};
+
+    let hand = |ranks: &[Rank]| {
+        Hand::from_cards(
+            ranks
+                .iter()
+                .map(|&rank| Card::new(rank, Suit::Hearts))
+                .collect(),
+        )
+    };
+
+    let soft_17 = hand(&[Rank::Ace, Rank::Six]);
+    assert_eq!(soft_17.hard_total(), 7);
+    assert_eq!(soft_17.soft_total(), 17);
+    assert!(soft_17.is_soft());
+    assert!(!soft_17.is_blackjack());
+
+    let hard_17 = hand(&[Rank::Ace, Rank::Six, Rank::Queen]);
+    assert_eq!(hard_17.soft_total(), 17);
+    assert!(!hard_17.is_soft());
+    assert!(!hard_17.is_bust());
+
+    assert!(hand(&[Rank::Ace, Rank::King]).is_blackjack());
+    assert!(!hand(&[Rank::Seven, Rank::Four, Rank::King]).is_blackjack());
+    assert!(hand(&[Rank::Ten, Rank::Six, Rank::Ace, Rank::Five]).is_bust());
+
+    assert!(hand(&[Rank::King, Rank::Queen]).is_pair());
+    assert!(!hand(&[Rank::King, Rank::Queen]).is_rank_pair());
+    assert!(hand(&[Rank::Eight, Rank::Eight]).is_rank_pair());
+    assert!(!hand(&[Rank::Eight, Rank::Eight, Rank::Eight]).is_pair());
+}
@@ -0,0 +1,530 @@
+use crate::chips::Chips;
+use crate::enums::{PayoutRounding, PlayerAction, ScriptError};
+use crate::models::{Card, PlayerHand};
+use crate::replay::SessionRecord;
+use crate::round::is_action_available;
+use crate::rules::HouseRules;
+
+use std::collections::VecDeque;
+use std::io::{BufRead, StdinLock};
+use std::path::Path;
+
+/// Where a seat's decisions come from, whether that is a person at a terminal,
+/// a script of decisions or a bot.
+pub trait PlayerInput {
+    /// The next bet, or `None` to leave the table.
+    fn choose_bet(&mut self, balance: Chips, rules: &HouseRules) -> Option<Chips>;
+
+    /// What to put on each of the table's side bets alongside `bet`, in the order the
+    /// rules list them, with nothing on any sat out. None are placed unless overridden.
+    fn choose_side_bets(
+        &mut self,
+        _balance: Chips,
+        _bet: Chips,
+        _rules: &HouseRules,
+    ) -> Vec<Chips> {
+        vec![]
+    }
+
+    /// The action to take on `hand`, or `None` if there are no more decisions to be had.
+    fn choose_action(
+        &mut self,
+        hand: &PlayerHand,
+        dealer_upcard: Card,
+        available_actions: &[PlayerAction],
+        rules: &HouseRules,
+    ) -> Option<PlayerAction>;
+}
+
+pub fn parse_player_action(input: &str) -> Option<PlayerAction> {
+    let input = input.to_lowercase();
+    // a double for less is the letter followed by the amount, such as "d 5"
+    if let Some(amount) = input.trim().strip_prefix('d') {
+        if let Ok(amount) = amount.trim().parse::<Chips>() {
+            return Some(PlayerAction::DoubleForLess(amount));
+        }
+    }
+
+    match input.trim() {
+        "h" => Some(PlayerAction::Hit),
+        "s" => Some(PlayerAction::Stay),
+        "d" => Some(PlayerAction::DoubleDown),
+        "p" => Some(PlayerAction::Split),
+        "i" => Some(PlayerAction::Insurance),
+        "n" => Some(PlayerAction::DeclineInsurance),
+        "m" => Some(PlayerAction::EvenMoney),
+        "r" => Some(PlayerAction::Surrender),
+        "u" => Some(PlayerAction::Rescue),
+        "w" => Some(PlayerAction::Switch),
+        "t" => Some(PlayerAction::Twist),
+        "k" => Some(PlayerAction::Stick),
+        "b" => Some(PlayerAction::Buy),
+        _ => None,
+    }
+}
+
+pub fn get_action_verb(player_action: PlayerAction) -> &'static str {
+    match player_action {
+        PlayerAction::Hit => "hit",
+        PlayerAction::Stay => "stay",
+        PlayerAction::DoubleDown => "double down",
+        PlayerAction::DoubleForLess(_) => "double for less",
+        PlayerAction::Split => "split",
+        PlayerAction::Insurance => "take insurance",
+        PlayerAction::DeclineInsurance => "decline insurance",
+        PlayerAction::EvenMoney => "take even money",
+        PlayerAction::Surrender => "surrender",
+        PlayerAction::Rescue => "rescue",
+        PlayerAction::Switch => "switch",
+        PlayerAction::Twist => "twist",
+        PlayerAction::Stick => "stick",
+        PlayerAction::Buy => "buy",
+    }
+}
+
+pub fn format_player_actions(player_actions: &[PlayerAction]) -> String {
+    player_actions
+        .iter()
+        .map(|action| match action {
+            PlayerAction::Hit => "(h)it".to_string(),
+            PlayerAction::Stay => "(s)tay".to_string(),
+            PlayerAction::DoubleDown => "(d)ouble down".to_string(),
+            PlayerAction::DoubleForLess(max_amount) => {
+                format!("(d <amount>) double for up to {}", max_amount)
+            }
+            PlayerAction::Split => "s(p)lit".to_string(),
+            PlayerAction::Insurance => "(i)nsurance".to_string(),
+            PlayerAction::DeclineInsurance => "(n)o insurance".to_string(),
+            PlayerAction::EvenMoney => "even (m)oney".to_string(),
+            PlayerAction::Surrender => "su(r)render".to_string(),
+            PlayerAction::Rescue => "resc(u)e".to_string(),
+            PlayerAction::Switch => "s(w)itch".to_string(),
+            PlayerAction::Twist => "(t)wist".to_string(),
+            PlayerAction::Stick => "stic(k)".to_string(),
+            PlayerAction::Buy => "(b)uy".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Prompts a person for every decision and keeps asking until the answer is valid.
+pub struct TerminalInput<R: BufRead> {
+    reader: R,
+    // prompts stay off stdout when it carries the game as json events
+    is_prompting_on_stderr: bool,
+}
+
+impl TerminalInput<StdinLock<'static>> {
+    pub fn stdin() -> Self {
+        TerminalInput::new(std::io::stdin().lock())
+    }
+}
+
+impl<R: BufRead> TerminalInput<R> {
+    pub fn new(reader: R) -> Self {
+        TerminalInput {
+            reader,
+            is_prompting_on_stderr: false,
+        }
+    }
+
+    pub fn with_prompts_on_stderr(mut self, is_prompting_on_stderr: bool) -> Self {
+        self.is_prompting_on_stderr = is_prompting_on_stderr;
+        self
+    }
+
+    fn prompt(&self, message: std::fmt::Arguments) {
+        if self.is_prompting_on_stderr {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    // None once the input has been closed
+    fn read_line(&mut self) -> Option<String> {
+        let mut player_action_buffer = String::new();
+        let bytes_read = self
+            .reader
+            .read_line(&mut player_action_buffer)
+            .expect("Error: failed to read input from stdin.");
+
+        if bytes_read == 0 {
+            None
+        } else {
+            Some(player_action_buffer)
+        }
+    }
+}
+
+impl<R: BufRead> PlayerInput for TerminalInput<R> {
+    fn choose_bet(&mut self, balance: Chips, rules: &HouseRules) -> Option<Chips> {
+        self.prompt(format_args!("You now have {} chips.", balance));
+        self.prompt(format_args!(
+            "How much would you like to bet? (e)xit if you would like to leave the table."
+        ));
+
+        loop {
+            let player_action_buffer = self.read_line()?;
+
+            match player_action_buffer.to_lowercase().trim() {
+                "e" => return None,
+                val => match val.parse::<Chips>() {
+                    Ok(bet) => {
+                        let hand_count = rules.starting_hand_count();
+                        let stake = bet.checked_mul(hand_count as i64);
+                        if hand_count > 1 && stake.is_none_or(|stake| stake > balance) {
+                            self.prompt(format_args!(
+                                "Your bet goes on each of your {} hands, so {} only covers a bet of {}. Please enter your bet again.",
+                                hand_count,
+                                balance,
+                                balance.scale(1, hand_count as u32, PayoutRounding::HalfChip)
+                            ));
+                        } else if bet > balance {
+                            self.prompt(format_args!(
+                                "You can only bet up to your balance {}. Please enter your bet again.",
+                                balance
+                            ));
+                        } else if bet < rules.min_bet {
+                            self.prompt(format_args!(
+                                "The table minimum is {} chips. Please enter your bet again.",
+                                rules.min_bet
+                            ));
+                        } else if rules.max_bet.is_some_and(|max_bet| bet > max_bet) {
+                            self.prompt(format_args!(
+                                "The table maximum is {} chips. Please enter your bet again.",
+                                rules.max_bet.unwrap_or_default()
+                            ));
+                        } else if !rules.is_wager_allowed(bet) {
+                            self.prompt(format_args!(
+                                "Bets go up in steps of {} chips. Please enter your bet again.",
+                                rules.bet_increment.unwrap_or_default()
+                            ));
+                        } else {
+                            return Some(bet);
+                        }
+                    }
+                    Err(_) => {
+                        self.prompt(format_args!(
+                            "Invalid input. Please enter your bet or (e)xit the table."
+                        ));
+                    }
+                },
+            }
+        }
+    }
+
+    fn choose_side_bets(&mut self, balance: Chips, bet: Chips, rules: &HouseRules) -> Vec<Chips> {
+        let mut balance_left = balance - bet * rules.starting_hand_count() as i64;
+        let mut side_bets = vec![];
+
+        for side_bet_rule in &rules.side_bets {
+            self.prompt(format_args!(
+                "How much would you like on {}? Press enter to sit it out.",
+                side_bet_rule.side_bet().name()
+            ));
+
+            let side_bet = loop {
+                // a closed input sits out the rest
+                let Some(player_input_buffer) = self.read_line() else {
+                    return side_bets;
+                };
+
+                match player_input_buffer.trim() {
+                    "" => break Chips::ZERO,
+                    val => match val.parse::<Chips>() {
+                        Ok(side_bet) if side_bet < Chips::ZERO => {
+                            self.prompt(format_args!("Please enter an amount of at least 0."));
+                        }
+                        Ok(side_bet) if side_bet > balance_left => {
+                            self.prompt(format_args!(
+                                "You only have {} left to bet. Please enter your side bet again.",
+                                balance_left
+                            ));
+                        }
+                        Ok(side_bet) => break side_bet,
+                        Err(_) => {
+                            self.prompt(format_args!(
+                                "Invalid input. Please enter your side bet or press enter."
+                            ));
+                        }
+                    },
+                }
+            };
+            balance_left -= side_bet;
+            side_bets.push(side_bet);
+        }
+
+        side_bets
+    }
+
+    fn choose_action(
+        &mut self,
+        _hand: &PlayerHand,
+        _dealer_upcard: Card,
+        available_actions: &[PlayerAction],
+        rules: &HouseRules,
+    ) -> Option<PlayerAction> {
+        self.prompt(format_args!("{}", format_player_actions(available_actions)));
+
+        loop {
+            let player_action_buffer = self.read_line()?;
+
+            match parse_player_action(&player_action_buffer) {
+                Some(action) if is_action_available(rules, available_actions, action) => {
+                    return Some(action)
+                }
+                Some(PlayerAction::DoubleForLess(_)) => {
+                    match available_actions.iter().find_map(|action| match action {
+                        PlayerAction::DoubleForLess(max_amount) => Some(max_amount),
+                        _ => None,
+                    }) {
+                        Some(max_amount) => self.prompt(format_args!(
+                            "You can double for {} to {} chips{}. Please enter a valid option.",
+                            rules.min_bet,
+                            max_amount,
+                            match rules.bet_increment {
+                                Some(bet_increment) => format!(", in steps of {}", bet_increment),
+                                None => String::new(),
+                            }
+                        )),
+                        None => self.prompt(format_args!(
+                            "You cannot double for less at this time. Please enter a valid option."
+                        )),
+                    }
+                    self.prompt(format_args!("{}", format_player_actions(available_actions)));
+                }
+                Some(action) => {
+                    self.prompt(format_args!(
+                        "You cannot {} at this time. Please enter a valid option.",
+                        get_action_verb(action)
+                    ));
+                    self.prompt(format_args!("{}", format_player_actions(available_actions)));
+                }
+                None => {
+                    self.prompt(format_args!("Please enter a valid option."));
+                    self.prompt(format_args!("{}", format_player_actions(available_actions)));
+                }
+            }
+        }
+    }
+}
+
+/// Plays back a fixed list of bets and actions, in the order they are asked for.
+///
+/// A script file holds what would be typed at the terminal, one entry per line:
+/// a bet amount, `e` to leave the table, or an action letter such as `h` or `s`.
+/// Blank lines and lines starting with `#` are ignored. Side bets are only played
+/// back from a session record.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    bets: VecDeque<Option<Chips>>,
+    side_bets: VecDeque<Vec<Chips>>,
+    actions: VecDeque<PlayerAction>,
+}
+
+impl ScriptedInput {
+    pub fn new(bets: Vec<Chips>, actions: Vec<PlayerAction>) -> Self {
+        ScriptedInput {
+            bets: bets.into_iter().map(Some).collect(),
+            side_bets: VecDeque::new(),
+            actions: actions.into_iter().collect(),
+        }
+    }
+
+    pub fn from_session_record(session_record: &SessionRecord) -> Self {
+        let mut scripted_input = ScriptedInput::new(
+            session_record
+                .rounds
+                .iter()
+                .map(|round| round.bet)
+                .collect(),
+            session_record
+                .rounds
+                .iter()
+                .flat_map(|round| round.actions.iter().copied())
+                .collect(),
+        );
+        scripted_input.side_bets = session_record
+            .rounds
+            .iter()
+            .map(|round| round.side_bets.clone())
+            .collect();
+        scripted_input
+    }
+
+    pub fn parse(script: &str) -> Result<Self, ScriptError> {
+        let mut scripted_input = ScriptedInput::default();
+
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.eq_ignore_ascii_case("e") {
+                scripted_input.bets.push_back(None);
+            } else if let Ok(bet) = line.parse::<Chips>() {
+                scripted_input.bets.push_back(Some(bet));
+            } else if let Some(action) = parse_player_action(line) {
+                scripted_input.actions.push_back(action);
+            } else {
+                return Err(ScriptError::InvalidLine {
+                    line_number: i + 1,
+                    line: line.to_string(),
+                });
+            }
+        }
+
+        Ok(scripted_input)
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self, ScriptError> {
+        let script = std::fs::read_to_string(path)
+            .map_err(|err| ScriptError::Io(path.display().to_string(), err))?;
+
+        ScriptedInput::parse(&script)
+    }
+}
+
+impl PlayerInput for ScriptedInput {
+    fn choose_bet(&mut self, _balance: Chips, _rules: &HouseRules) -> Option<Chips> {
+        self.bets.pop_front().flatten()
+    }
+
+    fn choose_side_bets(
+        &mut self,
+        _balance: Chips,
+        _bet: Chips,
+        _rules: &HouseRules,
+    ) -> Vec<Chips> {
+        self.side_bets.pop_front().unwrap_or_default()
+    }
+
+    fn choose_action(
+        &mut self,
+        _hand: &PlayerHand,
+        _dealer_upcard: Card,
+        _available_actions: &[PlayerAction],
+        _rules: &HouseRules,
+    ) -> Option<PlayerAction> {
+        self.actions.pop_front()
+    }
+}
+
+/// Bets a flat amount for a number of rounds and plays every hand with `strategy`.
+pub struct BotInput<F>
+where
+    F: FnMut(&PlayerHand, Card, &[PlayerAction]) -> PlayerAction,
+{
+    bet: Chips,
+    rounds_remaining: usize,
+    strategy: F,
+}
+
+impl<F> BotInput<F>
+where
+    F: FnMut(&PlayerHand, Card, &[PlayerAction]) -> PlayerAction,
+{
+    pub fn new(bet: Chips, rounds: usize, strategy: F) -> Self {
+        BotInput {
+            bet,
+            rounds_remaining: rounds,
+            strategy,
+        }
+    }
+}
+
+impl<F> PlayerInput for BotInput<F>
+where
+    F: FnMut(&PlayerHand, Card, &[PlayerAction]) -> PlayerAction,
+{
+    fn choose_bet(&mut self, balance: Chips, rules: &HouseRules) -> Option<Chips> {
+        if self.rounds_remaining == 0 || !rules.is_bet_allowed(balance, self.bet) {
+            return None;
+        }
+
+        self.rounds_remaining -= 1;
+        Some(self.bet)
+    }
+
+    fn choose_action(
+        &mut self,
+        hand: &PlayerHand,
+        dealer_upcard: Card,
+        available_actions: &[PlayerAction],
+        _rules: &HouseRules,
+    ) -> Option<PlayerAction> {
+        Some((self.strategy)(hand, dealer_upcard, available_actions))
+    }
+}
+
+/// Plays like the dealer does: hit anything under 17 and never double, split or insure.
+pub fn mimic_dealer_strategy(
+    hand: &PlayerHand,
+    _dealer_upcard: Card,
+    available_actions: &[PlayerAction],
+) -> PlayerAction {
+    if available_actions.contains(&PlayerAction::DeclineInsurance) {
+        PlayerAction::DeclineInsurance
+    } else if hand.hand.soft_total() < 17 {
+        if available_actions.contains(&PlayerAction::Twist) {
+            PlayerAction::Twist
+        } else {
+            PlayerAction::Hit
+        }
+    } else if available_actions.contains(&PlayerAction::Stick) {
+        PlayerAction::Stick
+    } else {
+        PlayerAction::Stay
+    }
+}
+
+#[test]
+fn test_scripted_and_terminal_input() {
+    use crate::enums::{Rank, Suit};
+    use crate::hand::Hand;
+
+    let hand = PlayerHand::new(
+        Hand::from_cards(vec![
+            Card::new(Rank::Eight, Suit::Clubs),
+            Card::new(Rank::Eight, Suit::Hearts),
+        ]),
+        Chips::new(10),
+    );
+    let dealer_upcard = Card::new(Rank::Six, Suit::Spades);
+    let rules = HouseRules::default();
+    let available_actions = [PlayerAction::Hit, PlayerAction::Stay];
+
+    let mut scripted_input = ScriptedInput::parse("# opening bet\n10\n\nh\nS\ne\n").unwrap();
+    assert_eq!(
+        scripted_input.choose_bet(Chips::new(500), &rules),
+        Some(Chips::new(10))
+    );
+    assert_eq!(
+        scripted_input.choose_action(&hand, dealer_upcard, &available_actions, &rules),
+        Some(PlayerAction::Hit)
+    );
+    assert_eq!(
+        scripted_input.choose_action(&hand, dealer_upcard, &available_actions, &rules),
+        Some(PlayerAction::Stay)
+    );
+    assert_eq!(scripted_input.choose_bet(Chips::new(500), &rules), None);
+    assert!(ScriptedInput::parse("10\nsurrender\n").is_err());
+    assert_eq!(
+        parse_player_action("D 2.5"),
+        Some(PlayerAction::DoubleForLess(Chips::from_half_chips(5)))
+    );
+
+    // invalid and unavailable answers are asked again, closed input ends the session
+    let mut terminal_input = TerminalInput::new("0\nten\n600\n25\nx\np\ns\n".as_bytes());
+    assert_eq!(
+        terminal_input.choose_bet(Chips::new(500), &rules),
+        Some(Chips::new(25))
+    );
+    assert_eq!(
+        terminal_input.choose_action(&hand, dealer_upcard, &available_actions, &rules),
+        Some(PlayerAction::Stay)
+    );
+    assert_eq!(terminal_input.choose_bet(Chips::new(500), &rules), None);
+}
@@ -0,0 +1,13 @@
+pub mod chips;
+pub mod dealer;
+pub mod deck;
+pub mod enums;
+pub mod game;
+pub mod hand;
+pub mod input;
+pub mod models;
+pub mod render;
+pub mod replay;
+pub mod round;
+pub mod rules;
+pub mod side_bets;
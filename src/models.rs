@@ -1,9 +1,37 @@
-use crate::enums::PlayerAction;
+use crate::chips::Chips;
+use crate::enums::{PlayerAction, Rank, Suit};
+use crate::hand::Hand;
+use crate::side_bets::SideBetResult;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlayerProfile {
-    pub balance: i32,
+    pub balance: Chips,
+    // running totals for each side bet the player has placed, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub side_bets: BTreeMap<String, SideBetStats>,
+}
+
+/// How a side bet has done over every round the player placed it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SideBetStats {
+    pub placed: u32,
+    pub won: u32,
+    pub wagered: Chips,
+    pub net: Chips,
+}
+
+impl SideBetStats {
+    pub fn record(&mut self, result: &SideBetResult) {
+        self.placed += 1;
+        if result.payout > Chips::ZERO {
+            self.won += 1;
+        }
+        self.wagered += result.bet;
+        self.net += result.payout;
+    }
 }
 
 pub struct Player {
@@ -11,17 +39,82 @@ pub struct Player {
 }
 
 pub struct PlayerHand {
-    pub cards: Vec<u8>,
-    pub bet: i32,
-    pub payout: Option<i32>,
+    pub hand: Hand,
+    pub bet: Chips,
+    // the part of the bet the hand was dealt with, before any split or double added to it
+    pub original_bet: Chips,
+    // the part of the bet put up by the house, which the player does not lose
+    pub free_bet: Chips,
+    pub payout: Option<Chips>,
+    // settled on sight by one of the table's hand rules
+    pub is_paid_by_hand_rule: bool,
     pub is_complete_taking_actions: bool,
     pub avaiable_actions: Vec<PlayerAction>,
     pub previous_actions_taken: Vec<PlayerAction>,
     pub is_starting_hand: bool,
+    // one of the hands a split made, which tables play differently from hands as dealt
+    pub is_split_hand: bool,
 }
 
-// pub struct Card {
-//     pub face_value: char,
-//     pub numeric_value: u8,
-//     pub is_visible: bool,
-// }
+impl PlayerHand {
+    /// A hand as it is dealt, before the player has acted on it.
+    pub fn new(hand: Hand, bet: Chips) -> Self {
+        PlayerHand {
+            hand,
+            bet,
+            original_bet: bet,
+            free_bet: Chips::ZERO,
+            payout: None,
+            is_paid_by_hand_rule: false,
+            is_complete_taking_actions: false,
+            avaiable_actions: vec![],
+            previous_actions_taken: vec![],
+            is_starting_hand: true,
+            is_split_hand: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card { rank, suit }
+    }
+
+    pub fn numeric_value(&self) -> u8 {
+        self.rank.numeric_value()
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.rank.face_value(), self.suit.symbol())
+    }
+}
+
+/// What can be seen at the table at one moment, with `None` standing in for a face down card.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableSnapshot {
+    pub dealer_cards: Vec<Option<Card>>,
+    pub player_hands: Vec<Vec<Card>>,
+    pub active_hand_index: Option<usize>,
+}
+
+#[test]
+fn test_card_values_and_display() {
+    // face cards count as 10, aces as 1 until a hand counts them as 11
+    for rank in [Rank::Ten, Rank::Jack, Rank::Queen, Rank::King] {
+        assert_eq!(Card::new(rank, Suit::Clubs).numeric_value(), 10);
+    }
+    assert_eq!(Card::new(Rank::Ace, Suit::Clubs).numeric_value(), 1);
+    assert_eq!(Card::new(Rank::Seven, Suit::Clubs).numeric_value(), 7);
+
+    assert_eq!(Card::new(Rank::King, Suit::Spades).to_string(), "K♠");
+    assert_eq!(Card::new(Rank::Ten, Suit::Hearts).to_string(), "10♥");
+    assert_eq!(Card::new(Rank::Ace, Suit::Diamonds).to_string(), "A♦");
+}
@@ -0,0 +1,374 @@
+use crate::chips::Chips;
+use crate::enums::{DealerStep, GameEvent, HandRule, PlayerAction};
+use crate::models::{Card, TableSnapshot};
+use crate::rules::HouseRules;
+
+use std::io::Write;
+use std::time::Duration;
+
+/// Receives every event of a game as it happens, to show it, log it or ignore it.
+pub trait GameRenderer {
+    fn render(&mut self, event: &GameEvent);
+}
+
+/// Writes the game to stdout the way it reads at the terminal.
+pub struct TextRenderer {
+    dealer_step_delay: Duration,
+}
+
+impl TextRenderer {
+    pub fn new(dealer_step_delay: Duration) -> Self {
+        TextRenderer { dealer_step_delay }
+    }
+}
+
+impl Default for TextRenderer {
+    fn default() -> Self {
+        TextRenderer::new(Duration::from_millis(1000))
+    }
+}
+
+impl GameRenderer for TextRenderer {
+    fn render(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::TableOpened { rules } => {
+                for line in describe_table(rules) {
+                    println!("{}", line);
+                }
+            }
+            GameEvent::ShoeShuffled => {
+                println!("The cut card has been reached. Shuffling the shoe.");
+            }
+            GameEvent::Blackjack { player, dealer, .. } => {
+                if *player && *dealer {
+                    println!("You and the dealer hit blackjack!");
+                } else if *player {
+                    println!("You hit blackjack!");
+                } else {
+                    println!("The dealer hit blackjack!");
+                }
+            }
+            GameEvent::TableShown { table } => print_table(table),
+            GameEvent::ActionTaken {
+                action,
+                bet,
+                free_bet,
+                ..
+            } => match action {
+                PlayerAction::Hit => println!("You decided to hit!"),
+                PlayerAction::Stay => println!("You decided to stay!"),
+                PlayerAction::DoubleDown if *free_bet > Chips::ZERO => println!(
+                    "You decided to double down! Your bet for this hand is now {}, {} of it on the house!",
+                    bet, free_bet
+                ),
+                PlayerAction::DoubleDown => println!(
+                    "You decided to double down! Your bet for this hand is now {}!",
+                    bet
+                ),
+                PlayerAction::DoubleForLess(_) => println!(
+                    "You decided to double for less! Your bet for this hand is now {}!",
+                    bet
+                ),
+                PlayerAction::Split => {}
+                PlayerAction::Insurance => println!("You decided to take insurance!"),
+                PlayerAction::DeclineInsurance => println!("You decided not to take insurance."),
+                PlayerAction::EvenMoney => println!("You decided to take even money!"),
+                PlayerAction::Surrender => {
+                    println!("You decided to surrender! Half of your bet is returned.")
+                }
+                PlayerAction::Twist => println!("You decided to twist!"),
+                PlayerAction::Stick => println!("You decided to stick!"),
+                PlayerAction::Buy => println!(
+                    "You decided to buy a card! Your bet for this hand is now {}!",
+                    bet
+                ),
+                PlayerAction::Switch => {
+                    println!("You decided to switch the second cards of your hands!")
+                }
+                PlayerAction::Rescue => {
+                    println!("You decided to rescue your double! Only your original bet is lost.")
+                }
+            },
+            GameEvent::InsuranceOffered {
+                cost,
+                is_even_money,
+            } => {
+                if *is_even_money {
+                    println!("The dealer shows an ace. Would you like even money?");
+                } else {
+                    println!(
+                        "The dealer shows an ace. Would you like insurance for {}?",
+                        cost
+                    );
+                }
+            }
+            GameEvent::SideBetSettled { result } => match &result.outcome {
+                Some(outcome) => println!(
+                    "Your {} side bet wins {} with a {}!",
+                    result.name, result.payout, outcome
+                ),
+                None => println!("You lost your {} side bet of {}.", result.name, result.bet),
+            },
+            GameEvent::InsuranceSettled { payout } => {
+                if *payout > Chips::ZERO {
+                    println!("Insurance pays {}!", payout);
+                } else {
+                    println!("You lost your insurance of {}.", payout.abs());
+                }
+            }
+            GameEvent::HandBusted { .. } => println!("Sorry you have busted!"),
+            GameEvent::HandRuleApplied { rule, payout, .. } => match rule {
+                HandRule::CardCharlie { cards } => {
+                    println!("{} card Charlie! Your hand wins.", cards)
+                }
+                HandRule::CardTrick { cards, .. } => {
+                    println!("{} card trick! Your hand wins {}.", cards, payout)
+                }
+                HandRule::TwentyOneWins => println!("21! Your hand wins."),
+                HandRule::CardCountTwentyOne { .. } => {
+                    println!("Bonus for a 21 of many cards! Your hand wins {}.", payout)
+                }
+                HandRule::ThreeCardTwentyOne { ranks, .. } => println!(
+                    "Bonus for {}-{}-{}! Your hand wins {}.",
+                    ranks[0].face_value(),
+                    ranks[1].face_value(),
+                    ranks[2].face_value(),
+                    payout
+                ),
+            },
+            GameEvent::DealerTurnStarted { .. } => println!("Dealer hand starts!"),
+            GameEvent::DealerActed { step } => {
+                // give the player a moment to follow each dealer card
+                std::thread::sleep(self.dealer_step_delay);
+                match step {
+                    DealerStep::Hit => println!("Dealer hits!"),
+                    DealerStep::Bust => {
+                        println!("Dealer hits!");
+                        println!("Dealer has busted!");
+                    }
+                    DealerStep::Stay => println!("Dealer stays!"),
+                }
+            }
+            GameEvent::HandsCompared {
+                dealer_total,
+                player_totals,
+                hand_count,
+            } => {
+                println!("Dealer has {}", dealer_total);
+                for (hand_index, hand_sum) in player_totals {
+                    if *hand_count > 1 {
+                        println!("Player hand {} has {}", hand_index + 1, hand_sum);
+                    } else {
+                        println!("Player has {}", hand_sum);
+                    }
+                }
+            }
+            GameEvent::HandSettled { payout, .. } => {
+                if *payout > Chips::ZERO {
+                    println!("You won {}!", payout.abs());
+                } else if *payout == Chips::ZERO {
+                    println!("Push!");
+                } else {
+                    println!("You lost {}!", payout.abs());
+                }
+            }
+            GameEvent::RoundSettled {
+                total_payout,
+                hand_count,
+            } => {
+                if *hand_count > 1 {
+                    if *total_payout > Chips::ZERO {
+                        println!("In total you won {}!", total_payout.abs());
+                    } else if *total_payout == Chips::ZERO {
+                        println!("In total it was a push!");
+                    } else {
+                        println!("In total you lost {}!", total_payout.abs());
+                    }
+                }
+            }
+            GameEvent::RoundStarted { .. }
+            | GameEvent::CardDealt { .. }
+            | GameEvent::DecisionRequired { .. } => {}
+        }
+    }
+}
+
+/// Ignores every event, for simulations and tests.
+pub struct SilentRenderer;
+
+impl GameRenderer for SilentRenderer {
+    fn render(&mut self, _event: &GameEvent) {}
+}
+
+/// Writes each event as one line of JSON.
+pub struct JsonRenderer<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        JsonRenderer { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> GameRenderer for JsonRenderer<W> {
+    fn render(&mut self, event: &GameEvent) {
+        serde_json::to_writer(&mut self.writer, event).expect("Error: Failed to write event.");
+        writeln!(self.writer).expect("Error: Failed to write event.");
+    }
+}
+
+/// The table rules a player is told about when they sit down, one line each.
+pub fn describe_table(rules: &HouseRules) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Blackjack pays {}. Dealer {} on soft 17.",
+        rules.blackjack_payout,
+        if rules.dealer_hits_soft_17 {
+            "hits"
+        } else {
+            "stands"
+        }
+    )];
+    match (rules.dealer_wins_ties, rules.dealer_wins_tied_naturals) {
+        (true, true) => lines.push("The dealer wins ties, blackjacks included.".to_string()),
+        (true, false) => {
+            lines.push("The dealer wins ties, but a blackjack still pushes.".to_string())
+        }
+        (false, true) => lines.push("A dealer blackjack beats yours.".to_string()),
+        (false, false) => {}
+    }
+    match (rules.max_bet, rules.bet_increment) {
+        (Some(max_bet), Some(bet_increment)) => lines.push(format!(
+            "Bets from {} to {} chips, in steps of {}.",
+            rules.min_bet, max_bet, bet_increment
+        )),
+        (Some(max_bet), None) => {
+            lines.push(format!("Bets from {} to {} chips.", rules.min_bet, max_bet))
+        }
+        (None, Some(bet_increment)) => lines.push(format!(
+            "Bets from {} chips, in steps of {}.",
+            rules.min_bet, bet_increment
+        )),
+        (None, None) => {}
+    }
+    if !rules.side_bets.is_empty() {
+        let side_bet_names: Vec<&str> = rules
+            .side_bets
+            .iter()
+            .map(|side_bet_rule| side_bet_rule.side_bet().name())
+            .collect();
+        lines.push(format!("Side bets: {}.", side_bet_names.join(", ")));
+    }
+    lines
+}
+
+pub fn print_table(table: &TableSnapshot) {
+    print_dealer_hand(&table.dealer_cards);
+    if table.player_hands.len() > 1 {
+        for (i, cards) in table.player_hands.iter().enumerate() {
+            print_hand(
+                format!("Player hand {} of {}", i + 1, table.player_hands.len()).as_str(),
+                cards,
+                table.active_hand_index == Some(i),
+            );
+        }
+    } else if table.player_hands.len() == 1 {
+        print_hand("Player", &table.player_hands[0], false);
+    }
+}
+
+pub fn print_hand(player_name: &str, cards: &[Card], display_active_marker: bool) {
+    let mut hand_string = "[".to_string();
+    for i in 0..cards.len() {
+        hand_string.push_str(&(cards[i].to_string()));
+        if i < cards.len() - 1 {
+            hand_string.push(' ');
+        }
+    }
+    hand_string.push(']');
+
+    if display_active_marker {
+        hand_string.push('*');
+    }
+
+    println!("{}: {}", player_name, hand_string);
+}
+
+pub fn print_dealer_hand(cards: &[Option<Card>]) {
+    let mut hand_string = "[".to_string();
+    for i in 0..cards.len() {
+        match cards[i] {
+            Some(card) => hand_string.push_str(&(card.to_string())),
+            None => hand_string.push('*'),
+        }
+        if i < cards.len() - 1 {
+            hand_string.push(' ');
+        }
+    }
+    hand_string.push(']');
+
+    println!("Dealer: {}", hand_string);
+}
+
+#[test]
+fn test_json_renderer_writes_event_lines() {
+    use crate::enums::{Rank, Seat, Suit};
+
+    let mut json_renderer = JsonRenderer::new(vec![]);
+    json_renderer.render(&GameEvent::CardDealt {
+        seat: Seat::Player { hand_index: 0 },
+        card: Some(Card::new(Rank::Ace, Suit::Spades)),
+    });
+    json_renderer.render(&GameEvent::CardDealt {
+        seat: Seat::Dealer,
+        card: None,
+    });
+
+    let output = String::from_utf8(json_renderer.into_inner()).unwrap();
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event"], "card_dealt");
+    assert_eq!(lines[0]["seat"]["seat"], "player");
+    assert_eq!(lines[0]["card"]["rank"], "Ace");
+    assert_eq!(lines[1]["seat"]["seat"], "dealer");
+    assert!(lines[1]["card"].is_null());
+}
+
+#[test]
+fn test_table_opened_shows_the_blackjack_payout() {
+    use crate::rules::PayoutRatio;
+
+    let rules = HouseRules::default();
+    assert_eq!(
+        describe_table(&rules),
+        vec!["Blackjack pays 3:2. Dealer stands on soft 17."]
+    );
+    let six_to_five_rules = HouseRules {
+        blackjack_payout: PayoutRatio::new(6, 5),
+        dealer_hits_soft_17: true,
+        ..HouseRules::default()
+    };
+    assert_eq!(
+        describe_table(&six_to_five_rules)[0],
+        "Blackjack pays 6:5. Dealer hits on soft 17."
+    );
+
+    // the whole rules reach anything reading the json events
+    let mut json_renderer = JsonRenderer::new(vec![]);
+    json_renderer.render(&GameEvent::TableOpened {
+        rules: six_to_five_rules,
+    });
+    let output = String::from_utf8(json_renderer.into_inner()).unwrap();
+    let line: serde_json::Value = serde_json::from_str(output.trim_end()).unwrap();
+    assert_eq!(line["event"], "table_opened");
+    assert_eq!(line["rules"]["blackjack_payout"], "6:5");
+    assert_eq!(line["rules"]["dealer_hits_soft_17"], true);
+}
@@ -0,0 +1,186 @@
+use crate::chips::Chips;
+use crate::deck::Shoe;
+use crate::enums::{PlayerAction, ReplayError, RoundState};
+use crate::hand::Hand;
+use crate::round::Round;
+use crate::rules::HouseRules;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Everything needed to deal a session again card for card: the shoe seed,
+/// the table rules and every bet and decision the player made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub seed: u64,
+    pub rules: HouseRules,
+    pub starting_balance: Chips,
+    pub rounds: Vec<RoundRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub bet: Chips,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub side_bets: Vec<Chips>,
+    pub actions: Vec<PlayerAction>,
+}
+
+/// The outcome of one round dealt again from a `SessionRecord`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedRound {
+    pub bet: Chips,
+    pub payout: Chips,
+    pub balance_after: Chips,
+    pub player_hands: Vec<Hand>,
+    pub dealer_hand: Hand,
+}
+
+impl SessionRecord {
+    pub fn new(seed: u64, rules: HouseRules, starting_balance: Chips) -> Self {
+        SessionRecord {
+            seed,
+            rules,
+            starting_balance,
+            rounds: vec![],
+        }
+    }
+
+    pub fn record_round(&mut self, bet: Chips, round: &Round) {
+        self.rounds.push(RoundRecord {
+            bet,
+            side_bets: round.side_bet_stakes().to_vec(),
+            actions: round.action_history().to_vec(),
+        });
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self, ReplayError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| ReplayError::Io(path.display().to_string(), err))?;
+
+        serde_json::from_str(&contents).map_err(|err| ReplayError::Parse(err.to_string()))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<(), ReplayError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| ReplayError::Parse(err.to_string()))?;
+
+        std::fs::write(path, contents)
+            .map_err(|err| ReplayError::Io(path.display().to_string(), err))
+    }
+
+    /// Deals every recorded round again from the same seed and applies the recorded decisions.
+    pub fn replay(&self) -> Result<Vec<ReplayedRound>, ReplayError> {
+        self.rules.validate().map_err(ReplayError::Rules)?;
+
+        let mut shoe = Shoe::seeded_for_rules(&self.rules, self.seed);
+        let mut balance = self.starting_balance;
+        let mut replayed_rounds = vec![];
+
+        for (i, round_record) in self.rounds.iter().enumerate() {
+            let round_number = i + 1;
+            let mut round = Round::deal_with_side_bets(
+                &mut shoe,
+                &self.rules,
+                balance,
+                round_record.bet,
+                &round_record.side_bets,
+            );
+            let mut actions = round_record.actions.iter();
+
+            while let RoundState::PlayerTurn { .. } = round.state() {
+                let action = actions.next().ok_or(ReplayError::MissingActions {
+                    round: round_number,
+                })?;
+                round
+                    .apply_action(*action)
+                    .map_err(|error| ReplayError::InvalidAction {
+                        round: round_number,
+                        error,
+                    })?;
+            }
+
+            if actions.next().is_some() {
+                return Err(ReplayError::UnusedActions {
+                    round: round_number,
+                });
+            }
+
+            while round.state() == RoundState::DealerTurn {
+                round
+                    .dealer_step()
+                    .map_err(|error| ReplayError::InvalidAction {
+                        round: round_number,
+                        error,
+                    })?;
+            }
+
+            let payout = round
+                .total_payout()
+                .expect("Error: replayed round finished without a payout.");
+            balance += payout;
+
+            replayed_rounds.push(ReplayedRound {
+                bet: round_record.bet,
+                payout,
+                balance_after: balance,
+                player_hands: round
+                    .player()
+                    .hands
+                    .iter()
+                    .map(|hand| hand.hand.clone())
+                    .collect(),
+                dealer_hand: round.dealer_hand().clone(),
+            });
+        }
+
+        Ok(replayed_rounds)
+    }
+}
+
+#[test]
+fn test_replay_matches_recorded_session() {
+    let rules = HouseRules::default();
+    let mut shoe = Shoe::seeded(rules.deck_count, rules.penetration_percent, 7);
+    let mut record = SessionRecord::new(7, rules.clone(), Chips::new(500));
+
+    let mut balance = Chips::new(500);
+    let mut payouts = vec![];
+    for _ in 0..20 {
+        let mut round = Round::deal(&mut shoe, &rules, balance, Chips::new(10));
+        while let RoundState::PlayerTurn { hand_index } = round.state() {
+            let action = if round.player().hands[hand_index].hand.soft_total() < 15 {
+                PlayerAction::Hit
+            } else {
+                PlayerAction::Stay
+            };
+            round.apply_action(action).unwrap();
+        }
+        while round.state() == RoundState::DealerTurn {
+            round.dealer_step().unwrap();
+        }
+        let payout = round.total_payout().unwrap();
+        balance += payout;
+        payouts.push(payout);
+        record.record_round(Chips::new(10), &round);
+    }
+
+    let record: SessionRecord =
+        serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+    let replayed_rounds = record.replay().unwrap();
+
+    assert_eq!(
+        replayed_rounds
+            .iter()
+            .map(|round| round.payout)
+            .collect::<Vec<_>>(),
+        payouts
+    );
+    assert_eq!(replayed_rounds.last().unwrap().balance_after, balance);
+
+    let mut tampered_record = record.clone();
+    tampered_record.seed += 1;
+    if let Ok(tampered_rounds) = tampered_record.replay() {
+        assert_ne!(tampered_rounds, replayed_rounds);
+    }
+}
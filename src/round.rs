@@ -0,0 +1,2148 @@
+use crate::chips::Chips;
+use crate::dealer::dealer_play_step;
+use crate::deck::Shoe;
+use crate::enums::{
+    DealerStep, DoubleRule, GameEvent, HandRule, HoleCardRule, PayoutRounding, PlayerAction, Rank,
+    RoundError, RoundState, Seat, Suit, Surrender,
+};
+use crate::hand::Hand;
+use crate::models::{Card, Player, PlayerHand, TableSnapshot};
+use crate::rules::{HouseRules, PayoutRatio};
+use crate::side_bets::{settle_side_bet, SideBetResult};
+
+use std::cmp::Ordering;
+
+/// A single round of blackjack between one player and the dealer.
+///
+/// The round never reads input or prints output: callers feed it
+/// `PlayerAction`s and dealer steps and inspect the returned `RoundState`.
+/// Everything that happens along the way is queued as `GameEvent`s for
+/// callers to collect with `take_events`.
+pub struct Round<'a> {
+    shoe: &'a mut Shoe,
+    rules: &'a HouseRules,
+    player: Player,
+    dealer_hand: Hand,
+    initial_bet: Chips,
+    player_working_balance: Chips,
+    insurance_bet: Option<Chips>,
+    insurance_payout: Option<Chips>,
+    side_bet_stakes: Vec<Chips>,
+    side_bet_results: Vec<SideBetResult>,
+    is_peek_pending: bool,
+    action_history: Vec<PlayerAction>,
+    events: Vec<GameEvent>,
+    state: RoundState,
+}
+
+impl<'a> Round<'a> {
+    /// Takes the initial bet from the player's balance and deals the opening cards,
+    /// reshuffling first if the cut card was reached last round.
+    ///
+    /// When the rules offer insurance and the dealer shows an ace, the round opens
+    /// with an insurance decision before the dealer checks for blackjack.
+    pub fn deal(
+        shoe: &'a mut Shoe,
+        rules: &'a HouseRules,
+        initial_player_balance: Chips,
+        initial_player_bet: Chips,
+    ) -> Self {
+        Round::deal_with_side_bets(shoe, rules, initial_player_balance, initial_player_bet, &[])
+    }
+
+    /// Deals like `deal` with `side_bets` placed too, one amount for each of the table's
+    /// side bets in order and nothing on any sat out. They settle on the opening cards.
+    pub fn deal_with_side_bets(
+        shoe: &'a mut Shoe,
+        rules: &'a HouseRules,
+        initial_player_balance: Chips,
+        initial_player_bet: Chips,
+        side_bets: &[Chips],
+    ) -> Self {
+        let mut events = vec![];
+        if shoe.is_cut_card_reached() {
+            shoe.shuffle();
+            events.push(GameEvent::ShoeShuffled);
+        }
+        events.push(GameEvent::RoundStarted {
+            balance: initial_player_balance,
+            bet: initial_player_bet,
+        });
+
+        let mut round = Round {
+            shoe,
+            rules,
+            player: Player {
+                hands: (0..rules.starting_hand_count())
+                    .map(|_| PlayerHand::new(Hand::new(), initial_player_bet))
+                    .collect(),
+            },
+            dealer_hand: Hand::new(),
+            initial_bet: initial_player_bet,
+            player_working_balance: initial_player_balance
+                - initial_player_bet * rules.starting_hand_count() as i64
+                - side_bets.iter().copied().sum(),
+            insurance_bet: None,
+            insurance_payout: None,
+            side_bet_stakes: side_bets.to_vec(),
+            side_bet_results: vec![],
+            is_peek_pending: false,
+            action_history: vec![],
+            events,
+            state: RoundState::Complete,
+        };
+
+        for hand_index in 0..round.player.hands.len() {
+            round.deal_to_player(hand_index);
+        }
+        // the first dealer card is the hole card, unless there is no hole card at all
+        let is_hole_card_dealt = rules.hole_card == HoleCardRule::Peek;
+        round.deal_to_dealer(!is_hole_card_dealt || rules.dealer_cards_exposed);
+
+        for hand_index in 0..round.player.hands.len() {
+            round.deal_to_player(hand_index);
+        }
+        if is_hole_card_dealt {
+            round.deal_to_dealer(true);
+        }
+        round.settle_side_bets();
+
+        if round.rules.insurance && round.dealer_upcard().rank == Rank::Ace {
+            round.offer_insurance();
+            return round;
+        }
+
+        round.peek_for_blackjack();
+        round.advance();
+        round
+    }
+
+    pub fn state(&self) -> RoundState {
+        self.state
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn dealer_hand(&self) -> &Hand {
+        &self.dealer_hand
+    }
+
+    pub fn player_working_balance(&self) -> Chips {
+        self.player_working_balance
+    }
+
+    /// The dealer's face up card. With a hole card it is the second card dealt to the dealer.
+    pub fn dealer_upcard(&self) -> Card {
+        match self.rules.hole_card {
+            HoleCardRule::Peek => self.dealer_hand.cards[1],
+            _ => self.dealer_hand.cards[0],
+        }
+    }
+
+    /// The dealer's face down card, if the rules deal one.
+    pub fn dealer_hole_card(&self) -> Option<Card> {
+        match self.rules.hole_card {
+            HoleCardRule::Peek => Some(self.dealer_hand.cards[0]),
+            _ => None,
+        }
+    }
+
+    /// Every action applied this round, in the order it was taken across all hands.
+    pub fn action_history(&self) -> &[PlayerAction] {
+        &self.action_history
+    }
+
+    /// What was put on each of the table's side bets, in the order the rules list them.
+    pub fn side_bet_stakes(&self) -> &[Chips] {
+        &self.side_bet_stakes
+    }
+
+    /// How each side bet that was placed settled.
+    pub fn side_bet_results(&self) -> &[SideBetResult] {
+        &self.side_bet_results
+    }
+
+    /// Drains the events queued since the last call.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn is_dealer_blackjack(&self) -> bool {
+        self.dealer_hand.is_blackjack()
+    }
+
+    pub fn table_snapshot(&self, is_hole_card_hidden: bool) -> TableSnapshot {
+        TableSnapshot {
+            dealer_cards: self
+                .dealer_hand
+                .cards
+                .iter()
+                .enumerate()
+                .map(|(i, &card)| {
+                    if i == 0
+                        && is_hole_card_hidden
+                        && self.dealer_hole_card().is_some()
+                        && !self.rules.dealer_cards_exposed
+                    {
+                        None
+                    } else {
+                        Some(card)
+                    }
+                })
+                .collect(),
+            player_hands: self
+                .player
+                .hands
+                .iter()
+                .map(|hand| hand.hand.cards.clone())
+                .collect(),
+            active_hand_index: get_first_incomplete_hand_index(&self.player),
+        }
+    }
+
+    /// Applies `action` to the active hand and returns the state the round moved to.
+    pub fn apply_action(&mut self, action: PlayerAction) -> Result<RoundState, RoundError> {
+        let hand_index = match self.state {
+            RoundState::PlayerTurn { hand_index } => hand_index,
+            _ => return Err(RoundError::NotPlayerTurn),
+        };
+
+        if !is_action_available(
+            self.rules,
+            &self.player.hands[hand_index].avaiable_actions,
+            action,
+        ) {
+            return Err(RoundError::ActionNotAvailable(action));
+        }
+
+        self.action_history.push(action);
+        self.player.hands[hand_index]
+            .previous_actions_taken
+            .push(action);
+
+        // with early surrender the dealer only peeks once the first decision is made,
+        // so anything but surrendering loses to a dealer blackjack
+        let is_peek_pending = std::mem::take(&mut self.is_peek_pending);
+        if is_peek_pending && action != PlayerAction::Surrender {
+            self.check_naturals();
+            if self.is_dealer_blackjack() {
+                self.advance();
+                return Ok(self.state);
+            }
+        }
+
+        match action {
+            PlayerAction::Hit | PlayerAction::Twist => {
+                self.push_action_taken(hand_index, action);
+                self.deal_to_player(hand_index);
+
+                let hand = &mut self.player.hands[hand_index];
+                if hand.hand.is_bust() {
+                    hand.is_complete_taking_actions = true;
+                    hand.payout = Some(get_losing_payout(hand));
+                    self.events.push(GameEvent::HandBusted { hand_index });
+                } else {
+                    self.apply_hand_rules(hand_index);
+                }
+            }
+            PlayerAction::Stay | PlayerAction::Stick => {
+                self.push_action_taken(hand_index, action);
+                self.player.hands[hand_index].is_complete_taking_actions = true;
+            }
+            PlayerAction::DoubleDown | PlayerAction::DoubleForLess(_) => {
+                let is_free = action == PlayerAction::DoubleDown
+                    && is_free_double(self.rules, &self.player.hands[hand_index]);
+                let hand = &mut self.player.hands[hand_index];
+                let double_amount = match action {
+                    PlayerAction::DoubleForLess(amount) => amount,
+                    _ => hand.bet,
+                };
+                if is_free {
+                    hand.free_bet += double_amount;
+                } else {
+                    self.player_working_balance -= double_amount;
+                }
+                hand.bet += double_amount;
+                self.push_action_taken(hand_index, action);
+                self.deal_to_player(hand_index);
+
+                let hand = &mut self.player.hands[hand_index];
+                // a double that can be rescued waits on the player to stay or take it back
+                hand.is_complete_taking_actions = !self.rules.double_down_rescue;
+                if hand.hand.is_bust() {
+                    hand.is_complete_taking_actions = true;
+                    hand.payout = Some(get_losing_payout(hand));
+                    self.events.push(GameEvent::HandBusted { hand_index });
+                } else {
+                    self.apply_hand_rules(hand_index);
+                }
+
+                // show the hit even though we'll continue on to the dealer for more suspense
+                if self.player.hands[hand_index].is_complete_taking_actions {
+                    self.show_table(true);
+                }
+            }
+            PlayerAction::Buy => {
+                // every pontoon hand, split ones included, is dealt with the initial bet
+                let buy_amount = self.initial_bet;
+                let hand = &mut self.player.hands[hand_index];
+                self.player_working_balance -= buy_amount;
+                hand.bet += buy_amount;
+                self.push_action_taken(hand_index, action);
+                self.deal_to_player(hand_index);
+
+                let hand = &mut self.player.hands[hand_index];
+                if hand.hand.is_bust() {
+                    hand.is_complete_taking_actions = true;
+                    hand.payout = Some(get_losing_payout(hand));
+                    self.events.push(GameEvent::HandBusted { hand_index });
+                } else {
+                    self.apply_hand_rules(hand_index);
+                }
+            }
+            PlayerAction::Split => {
+                self.push_action_taken(hand_index, action);
+
+                let is_free = is_free_split(self.rules, &self.player.hands[hand_index]);
+                let hand = &mut self.player.hands[hand_index];
+                if !is_free {
+                    self.player_working_balance -= hand.bet;
+                }
+                // neither hand is the one dealt, so neither can be a natural
+                hand.is_starting_hand = false;
+                hand.is_split_hand = true;
+
+                let split_card = hand.hand.cards.remove(0);
+                let new_hand = PlayerHand {
+                    free_bet: if is_free { hand.bet } else { Chips::ZERO },
+                    is_starting_hand: false,
+                    is_split_hand: true,
+                    // the split bet was added to the seat, it was not dealt
+                    original_bet: Chips::ZERO,
+                    ..PlayerHand::new(Hand::from_cards(vec![split_card]), hand.bet)
+                };
+                // the new hand sits next to the one it was split from
+                let new_hand_index = hand_index + 1;
+                self.player.hands.insert(new_hand_index, new_hand);
+
+                self.deal_to_player(hand_index);
+                self.deal_to_player(new_hand_index);
+                self.apply_hand_rules(hand_index);
+                self.apply_hand_rules(new_hand_index);
+
+                // split aces that cannot be split again are done after their one card
+                if split_card.rank == Rank::Ace && self.rules.split_aces_one_card {
+                    for split_hand_index in [hand_index, new_hand_index] {
+                        let split_hand = &self.player.hands[split_hand_index];
+                        if get_player_actions(
+                            self.rules,
+                            self.player_working_balance,
+                            self.player.hands.len(),
+                            split_hand,
+                        ) == [PlayerAction::Stay]
+                        {
+                            self.player.hands[split_hand_index].is_complete_taking_actions = true;
+                        }
+                    }
+                }
+            }
+            PlayerAction::Insurance => {
+                let insurance_bet = self.get_insurance_cost();
+                self.player_working_balance -= insurance_bet;
+                self.insurance_bet = Some(insurance_bet);
+                self.push_action_taken(hand_index, action);
+                self.peek_for_blackjack();
+            }
+            PlayerAction::EvenMoney => {
+                let hand = &mut self.player.hands[hand_index];
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(hand.bet);
+                self.push_action_taken(hand_index, action);
+                self.check_naturals();
+            }
+            PlayerAction::DeclineInsurance => {
+                self.push_action_taken(hand_index, action);
+                self.peek_for_blackjack();
+            }
+            PlayerAction::Surrender => {
+                self.push_action_taken(hand_index, action);
+
+                // half the bet is returned
+                let hand = &mut self.player.hands[hand_index];
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(hand.bet.scale(1, 2, PayoutRounding::HalfChip) - hand.bet);
+
+                if is_peek_pending {
+                    self.check_naturals();
+                }
+            }
+            PlayerAction::Switch => {
+                self.push_action_taken(hand_index, action);
+
+                let (first_hands, second_hands) = self.player.hands.split_at_mut(1);
+                std::mem::swap(
+                    &mut first_hands[0].hand.cards[1],
+                    &mut second_hands[0].hand.cards[1],
+                );
+                // a 21 made by switching cards is not a natural and pays even money
+                for hand in &mut self.player.hands {
+                    hand.is_starting_hand = false;
+                }
+                for switched_hand_index in 0..self.player.hands.len() {
+                    self.apply_hand_rules(switched_hand_index);
+                }
+            }
+            PlayerAction::Rescue => {
+                self.push_action_taken(hand_index, action);
+
+                // the double is returned and the original bet is lost
+                let hand = &mut self.player.hands[hand_index];
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(-self.initial_bet);
+            }
+        }
+
+        self.player.hands[hand_index].avaiable_actions = get_player_actions(
+            self.rules,
+            self.player_working_balance,
+            self.player.hands.len(),
+            &self.player.hands[hand_index],
+        );
+
+        self.advance();
+        Ok(self.state)
+    }
+
+    /// Plays a single dealer decision. Call repeatedly until the round is complete.
+    pub fn dealer_step(&mut self) -> Result<DealerStep, RoundError> {
+        if self.state != RoundState::DealerTurn {
+            return Err(RoundError::NotDealerTurn);
+        }
+
+        let dealer_hand_sum = self.dealer_hand.soft_total();
+
+        let shoe = &mut *self.shoe;
+        let mut dealt_card = None;
+        let dealer_step = dealer_play_step(self.rules, &mut self.dealer_hand, || {
+            let card = shoe.draw();
+            dealt_card = Some(card);
+            card
+        });
+        if let Some(card) = dealt_card {
+            self.events.push(GameEvent::CardDealt {
+                seat: Seat::Dealer,
+                card: Some(card),
+            });
+        }
+
+        match dealer_step {
+            DealerStep::Bust => {
+                // naturals are already paid, so a dealer 22 pushes every hand left
+                let is_push = self.rules.dealer_22_pushes && self.dealer_hand.soft_total() == 22;
+                for hand in &mut self.player.hands {
+                    if hand.payout.is_none() {
+                        hand.payout = Some(if is_push {
+                            Chips::ZERO
+                        } else {
+                            get_winning_payout(self.rules, hand)
+                        });
+                    }
+                }
+                self.push_dealer_acted(DealerStep::Bust);
+                self.complete();
+                return Ok(DealerStep::Bust);
+            }
+            DealerStep::Hit => {
+                self.push_dealer_acted(DealerStep::Hit);
+                return Ok(DealerStep::Hit);
+            }
+            DealerStep::Stay => {}
+        }
+
+        self.push_dealer_acted(DealerStep::Stay);
+
+        let mut player_totals = vec![];
+        for (hand_index, hand) in self.player.hands.iter_mut().enumerate() {
+            if hand.payout.is_none() {
+                let hand_sum = hand.hand.soft_total();
+                player_totals.push((hand_index, hand_sum));
+                hand.payout = Some(match hand_sum.cmp(&dealer_hand_sum) {
+                    // a dealer blackjack was settled before the dealer played, so a split
+                    // blackjack only meets a dealer 21 of more cards, which it beats
+                    _ if is_split_blackjack(self.rules, hand) => {
+                        get_winning_payout(self.rules, hand)
+                    }
+                    Ordering::Equal if self.rules.dealer_wins_ties => get_losing_payout(hand),
+                    Ordering::Equal => Chips::ZERO,
+                    Ordering::Greater => get_winning_payout(self.rules, hand),
+                    Ordering::Less => get_losing_payout(hand),
+                });
+            }
+        }
+        self.events.push(GameEvent::HandsCompared {
+            dealer_total: dealer_hand_sum,
+            player_totals,
+            hand_count: self.player.hands.len(),
+        });
+
+        self.complete();
+        Ok(DealerStep::Stay)
+    }
+
+    /// The net amount won or lost over all hands and insurance, once the round is complete.
+    pub fn total_payout(&self) -> Option<Chips> {
+        if self.state != RoundState::Complete {
+            return None;
+        }
+
+        let hands_payout: Option<Chips> = self.player.hands.iter().map(|hand| hand.payout).sum();
+        hands_payout.map(|payout| {
+            payout + self.insurance_payout.unwrap_or_default() + self.side_bets_payout()
+        })
+    }
+
+    fn side_bets_payout(&self) -> Chips {
+        self.side_bet_results
+            .iter()
+            .map(|result| result.payout)
+            .sum()
+    }
+
+    // side bets only look at the opening cards, so they settle before anything is played
+    fn settle_side_bets(&mut self) {
+        let player_hand = &self.player.hands[0].hand;
+        let player_cards = [player_hand.cards[0], player_hand.cards[1]];
+        let dealer_upcard = self.dealer_upcard();
+
+        for (side_bet_rule, &bet) in self.rules.side_bets.iter().zip(&self.side_bet_stakes) {
+            if bet == Chips::ZERO {
+                continue;
+            }
+
+            let result = settle_side_bet(
+                side_bet_rule.side_bet(),
+                bet,
+                player_cards,
+                dealer_upcard,
+                self.rules.payout_rounding,
+            );
+            // winnings are back in hand straight away
+            if result.payout > Chips::ZERO {
+                self.player_working_balance += bet + result.payout;
+            }
+            self.events.push(GameEvent::SideBetSettled {
+                result: result.clone(),
+            });
+            self.side_bet_results.push(result);
+        }
+    }
+
+    // insurance covers every hand dealt, for up to half of their bets
+    fn get_insurance_cost(&self) -> Chips {
+        let total_bet: Chips = self.player.hands.iter().map(|hand| hand.bet).sum();
+        total_bet
+            .scale(1, 2, PayoutRounding::HalfChip)
+            .min(self.player_working_balance)
+    }
+
+    fn offer_insurance(&mut self) {
+        let insurance_cost = self.get_insurance_cost();
+        // even money settles a single blackjack, other hands dealt alongside it need insuring
+        let is_even_money =
+            self.player.hands.len() == 1 && self.player.hands[0].hand.is_blackjack();
+        let hand = &mut self.player.hands[0];
+
+        hand.avaiable_actions = if is_even_money {
+            vec![PlayerAction::EvenMoney, PlayerAction::DeclineInsurance]
+        } else if insurance_cost > Chips::ZERO {
+            vec![PlayerAction::Insurance, PlayerAction::DeclineInsurance]
+        } else {
+            vec![PlayerAction::DeclineInsurance]
+        };
+        self.state = RoundState::PlayerTurn { hand_index: 0 };
+
+        self.show_table(true);
+        self.events.push(GameEvent::InsuranceOffered {
+            cost: insurance_cost,
+            is_even_money,
+        });
+        self.events.push(GameEvent::DecisionRequired {
+            hand_index: 0,
+            available_actions: self.player.hands[0].avaiable_actions.clone(),
+        });
+    }
+
+    fn peek_for_blackjack(&mut self) {
+        if self.rules.hole_card != HoleCardRule::Peek {
+            // naturals wait for the dealer's second card, there is nothing to play
+            for hand in &mut self.player.hands {
+                if hand.hand.is_blackjack() {
+                    hand.is_complete_taking_actions = true;
+                }
+            }
+        } else if self.rules.surrender == Surrender::Early
+            && !self.player.hands[0].hand.is_blackjack()
+        {
+            // early surrender is decided before the dealer peeks
+            self.is_peek_pending = true;
+        } else {
+            self.check_naturals();
+        }
+    }
+
+    // without a hole card the dealer takes their second card once the player is done
+    fn deal_dealer_second_card(&mut self) {
+        // hands already busted or surrendered keep what they were settled for, and a
+        // natural pushes against the dealer's, but a hand rule only pays if there is
+        // no dealer blackjack
+        let is_waiting_on_dealer: Vec<bool> = self
+            .player
+            .hands
+            .iter()
+            .map(|hand| {
+                (hand.payout.is_none() || hand.is_paid_by_hand_rule)
+                    && !(hand.is_starting_hand && hand.hand.is_blackjack())
+            })
+            .collect();
+
+        self.deal_to_dealer(true);
+        if self.is_dealer_blackjack() {
+            for hand in &mut self.player.hands {
+                if hand.is_paid_by_hand_rule {
+                    hand.payout = None;
+                }
+            }
+        }
+        if !self.check_naturals() {
+            self.show_table(false);
+        }
+
+        if self.rules.hole_card == HoleCardRule::NoHoleCardOriginalBetOnly
+            && self.is_dealer_blackjack()
+        {
+            // doubled and split bets are returned, only the original bets are lost
+            for (hand_index, hand) in self.player.hands.iter_mut().enumerate() {
+                if is_waiting_on_dealer[hand_index] {
+                    hand.payout = Some(-hand.original_bet);
+                }
+            }
+        }
+    }
+
+    // the dealer peeks for blackjack, settling insurance and any naturals,
+    // and returns whether there was a blackjack
+    fn check_naturals(&mut self) -> bool {
+        let is_dealer_blackjack = self.is_dealer_blackjack();
+
+        // insurance pays 2:1
+        if let Some(insurance_bet) = self.insurance_bet {
+            let insurance_payout = if is_dealer_blackjack {
+                insurance_bet * 2
+            } else {
+                -insurance_bet
+            };
+            self.insurance_payout = Some(insurance_payout);
+            self.events.push(GameEvent::InsuranceSettled {
+                payout: insurance_payout,
+            });
+        }
+
+        let mut is_any_blackjack = is_dealer_blackjack;
+        for (hand_index, hand) in self.player.hands.iter_mut().enumerate() {
+            // even money was already paid
+            if hand.payout.is_some() {
+                continue;
+            }
+
+            // a two card 21 after a split is not a natural
+            let is_hand_blackjack = hand.is_starting_hand && hand.hand.is_blackjack();
+            is_any_blackjack |= is_hand_blackjack;
+            if is_hand_blackjack && is_dealer_blackjack {
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(if self.rules.dealer_wins_tied_naturals {
+                    get_losing_payout(hand)
+                } else {
+                    Chips::ZERO
+                });
+            } else if is_hand_blackjack {
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(
+                    self.rules
+                        .blackjack_payout
+                        .apply(hand.bet, self.rules.payout_rounding),
+                );
+            } else if is_dealer_blackjack {
+                hand.is_complete_taking_actions = true;
+                hand.payout = Some(get_losing_payout(hand));
+            }
+
+            if is_hand_blackjack || is_dealer_blackjack {
+                self.events.push(GameEvent::Blackjack {
+                    hand_index,
+                    player: is_hand_blackjack,
+                    dealer: is_dealer_blackjack,
+                });
+            }
+        }
+
+        // we'll show all cards if there is a blackjack as for now
+        // the game would immediately end - let's let players count cards ;)
+        if is_any_blackjack {
+            self.show_table(false);
+        }
+        is_any_blackjack
+    }
+
+    fn advance(&mut self) {
+        if let Some(hand_index) = get_first_incomplete_hand_index(&self.player) {
+            // hands created by a split were dealt before later hands spent any balance
+            self.player.hands[hand_index].avaiable_actions = get_player_actions(
+                self.rules,
+                self.player_working_balance,
+                self.player.hands.len(),
+                &self.player.hands[hand_index],
+            );
+            if is_switch_available(self.rules, &self.player) {
+                self.player.hands[hand_index]
+                    .avaiable_actions
+                    .push(PlayerAction::Switch);
+            }
+            self.state = RoundState::PlayerTurn { hand_index };
+
+            self.show_table(true);
+            self.events.push(GameEvent::DecisionRequired {
+                hand_index,
+                available_actions: self.player.hands[hand_index].avaiable_actions.clone(),
+            });
+        } else if self.player.hands.iter().any(|hand| hand.payout.is_none())
+            || (self.insurance_bet.is_some() && self.insurance_payout.is_none())
+            || (self.dealer_hand.len() == 1
+                && self
+                    .player
+                    .hands
+                    .iter()
+                    .any(|hand| hand.is_paid_by_hand_rule))
+        {
+            self.state = RoundState::DealerTurn;
+            self.events.push(GameEvent::DealerTurnStarted {
+                hole_card: self.dealer_hole_card(),
+            });
+
+            if self.dealer_hand.len() == 1 {
+                self.deal_dealer_second_card();
+                if self.player.hands.iter().all(|hand| hand.payout.is_some()) {
+                    self.complete();
+                }
+            }
+        } else {
+            self.complete();
+        }
+    }
+
+    fn complete(&mut self) {
+        self.state = RoundState::Complete;
+
+        for (hand_index, hand) in self.player.hands.iter().enumerate() {
+            self.events.push(GameEvent::HandSettled {
+                hand_index,
+                payout: hand.payout.expect("Error payout does not have value."),
+            });
+        }
+        self.events.push(GameEvent::RoundSettled {
+            total_payout: self
+                .player
+                .hands
+                .iter()
+                .filter_map(|hand| hand.payout)
+                .sum::<Chips>()
+                + self.insurance_payout.unwrap_or_default()
+                + self.side_bets_payout(),
+            hand_count: self.player.hands.len(),
+        });
+    }
+
+    // settles the hand straight away if it makes a shape the table pays on sight
+    fn apply_hand_rules(&mut self, hand_index: usize) {
+        if let Some((rule, payout)) =
+            get_hand_rule_payout(self.rules, &self.player.hands[hand_index])
+        {
+            let hand = &mut self.player.hands[hand_index];
+            hand.is_complete_taking_actions = true;
+            hand.payout = Some(payout);
+            hand.is_paid_by_hand_rule = true;
+            self.events.push(GameEvent::HandRuleApplied {
+                hand_index,
+                rule,
+                payout,
+            });
+        }
+    }
+
+    fn deal_to_player(&mut self, hand_index: usize) {
+        let card = self.shoe.draw();
+        self.player.hands[hand_index].hand.push(card);
+        self.events.push(GameEvent::CardDealt {
+            seat: Seat::Player { hand_index },
+            card: Some(card),
+        });
+    }
+
+    fn deal_to_dealer(&mut self, is_face_up: bool) {
+        let card = self.shoe.draw();
+        self.dealer_hand.push(card);
+        self.events.push(GameEvent::CardDealt {
+            seat: Seat::Dealer,
+            card: is_face_up.then_some(card),
+        });
+    }
+
+    fn push_action_taken(&mut self, hand_index: usize, action: PlayerAction) {
+        self.events.push(GameEvent::ActionTaken {
+            hand_index,
+            action,
+            bet: self.player.hands[hand_index].bet,
+            free_bet: self.player.hands[hand_index].free_bet,
+        });
+    }
+
+    fn push_dealer_acted(&mut self, step: DealerStep) {
+        self.events.push(GameEvent::DealerActed { step });
+        self.show_table(false);
+    }
+
+    fn show_table(&mut self, is_hole_card_hidden: bool) {
+        self.events.push(GameEvent::TableShown {
+            table: self.table_snapshot(is_hole_card_hidden),
+        });
+    }
+}
+
+pub fn get_first_incomplete_hand_index(player: &Player) -> Option<usize> {
+    player
+        .hands
+        .iter()
+        .position(|hand| !hand.is_complete_taking_actions)
+}
+
+/// Whether the second cards of the two starting hands may still be switched: only
+/// before either hand has been played, once any insurance is decided.
+pub fn is_switch_available(rules: &HouseRules, player: &Player) -> bool {
+    rules.switch_hands
+        && player.hands.len() == 2
+        && player.hands.iter().all(|hand| {
+            hand.is_starting_hand
+                && !hand.is_complete_taking_actions
+                && hand.previous_actions_taken.iter().all(|action| {
+                    matches!(
+                        action,
+                        PlayerAction::Insurance | PlayerAction::DeclineInsurance
+                    )
+                })
+        })
+}
+
+/// Whether `action` is one of `available_actions`. A double for less is available
+/// for any amount the table limits allow up to the amount offered.
+pub fn is_action_available(
+    rules: &HouseRules,
+    available_actions: &[PlayerAction],
+    action: PlayerAction,
+) -> bool {
+    match action {
+        PlayerAction::DoubleForLess(amount) => available_actions.iter().any(|available_action| {
+            matches!(available_action, PlayerAction::DoubleForLess(max_amount)
+                if rules.is_wager_allowed(amount) && amount <= *max_amount)
+        }),
+        _ => available_actions.contains(&action),
+    }
+}
+
+/// The first of the table's `hand_rules` that settles `player_hand` as it stands,
+/// with what it pays.
+pub fn get_hand_rule_payout(
+    rules: &HouseRules,
+    player_hand: &PlayerHand,
+) -> Option<(HandRule, Chips)> {
+    let hand = &player_hand.hand;
+    let is_twenty_one = hand.soft_total() == 21;
+    // bonuses are not paid on doubled hands
+    let is_doubled = player_hand.previous_actions_taken.iter().any(|action| {
+        matches!(
+            action,
+            PlayerAction::DoubleDown | PlayerAction::DoubleForLess(_)
+        )
+    });
+    let bonus = |payout: PayoutRatio| payout.apply(player_hand.bet, rules.payout_rounding);
+
+    rules.hand_rules.iter().find_map(|&rule| match rule {
+        HandRule::CardCharlie { cards } => {
+            (hand.len() >= cards as usize && !hand.is_bust()).then_some((rule, player_hand.bet))
+        }
+        HandRule::CardTrick { cards, payout } => {
+            (hand.len() >= cards as usize && !hand.is_bust()).then(|| (rule, bonus(payout)))
+        }
+        HandRule::TwentyOneWins => is_twenty_one.then_some((rule, player_hand.bet)),
+        HandRule::CardCountTwentyOne { cards, payout } => {
+            (is_twenty_one && !is_doubled && hand.len() >= cards as usize)
+                .then(|| (rule, bonus(payout)))
+        }
+        HandRule::ThreeCardTwentyOne {
+            ranks,
+            mixed,
+            suited,
+            spades,
+        } => {
+            let mut hand_ranks: Vec<Rank> = hand.cards.iter().map(|card| card.rank).collect();
+            let mut bonus_ranks = ranks.to_vec();
+            hand_ranks.sort_by_key(|&rank| rank as u8);
+            bonus_ranks.sort_by_key(|&rank| rank as u8);
+            if is_doubled || hand_ranks != bonus_ranks {
+                return None;
+            }
+
+            let first_suit = hand.cards[0].suit;
+            let payout = if hand.cards.iter().all(|card| card.suit == Suit::Spades) {
+                spades
+            } else if hand.cards.iter().all(|card| card.suit == first_suit) {
+                suited
+            } else {
+                mixed
+            };
+            Some((rule, bonus(payout)))
+        }
+    })
+}
+
+/// What a losing hand costs the player: the bet, less anything the house put up.
+pub fn get_losing_payout(player_hand: &PlayerHand) -> Chips {
+    player_hand.free_bet - player_hand.bet
+}
+
+/// Whether the house puts up a double down on `player_hand`: a hard 9, 10 or 11
+/// of two cards at tables with free doubles.
+pub fn is_free_double(rules: &HouseRules, player_hand: &PlayerHand) -> bool {
+    rules.free_doubles
+        && player_hand.hand.len() == 2
+        && !player_hand.hand.is_soft()
+        && (9..=11).contains(&player_hand.hand.soft_total())
+}
+
+/// Whether the house puts up a split of `player_hand`: any pair but tens at tables
+/// with free splits.
+pub fn is_free_split(rules: &HouseRules, player_hand: &PlayerHand) -> bool {
+    rules.free_splits
+        && player_hand.hand.is_pair()
+        && player_hand.hand.cards[0].rank.numeric_value() != 10
+}
+
+/// Whether `player_hand` is a two card 21 after a split that the rules count as blackjack.
+pub fn is_split_blackjack(rules: &HouseRules, player_hand: &PlayerHand) -> bool {
+    rules.blackjack_after_split && player_hand.is_split_hand && player_hand.hand.is_blackjack()
+}
+
+/// What a hand that beats the dealer wins: even money, or the blackjack payout for
+/// a two card 21 after a split when the rules count it as blackjack.
+pub fn get_winning_payout(rules: &HouseRules, player_hand: &PlayerHand) -> Chips {
+    if is_split_blackjack(rules, player_hand) {
+        rules
+            .blackjack_payout
+            .apply(player_hand.bet, rules.payout_rounding)
+    } else {
+        player_hand.bet
+    }
+}
+
+pub fn get_player_actions(
+    rules: &HouseRules,
+    player_working_balance: Chips,
+    player_hand_count: usize,
+    player_hand: &PlayerHand,
+) -> Vec<PlayerAction> {
+    // a double that can be rescued may only stay or be rescued
+    if rules.double_down_rescue
+        && matches!(
+            player_hand.previous_actions_taken.last(),
+            Some(PlayerAction::DoubleDown | PlayerAction::DoubleForLess(_))
+        )
+    {
+        return vec![PlayerAction::Stay, PlayerAction::Rescue];
+    }
+
+    let is_split_aces = player_hand.is_split_hand
+        && player_hand
+            .hand
+            .cards
+            .first()
+            .is_some_and(|card| card.rank == Rank::Ace);
+    let is_resplit_allowed = if is_split_aces {
+        rules.resplit_aces
+    } else {
+        rules.allow_resplit
+    };
+    let is_split_available = player_hand.hand.is_pair()
+        && (!player_hand.is_split_hand || is_resplit_allowed)
+        && player_hand_count < rules.max_hands_per_seat as usize
+        && rules.is_wager_allowed(player_hand.bet)
+        && (player_hand.bet <= player_working_balance || is_free_split(rules, player_hand));
+
+    // split aces may only stay with their one card, or be split again
+    if is_split_aces && rules.split_aces_one_card {
+        let mut player_actions = vec![PlayerAction::Stay];
+        if is_split_available {
+            player_actions.push(PlayerAction::Split);
+        }
+        return player_actions;
+    }
+
+    if rules.pontoon_actions {
+        return get_pontoon_actions(
+            rules,
+            player_working_balance,
+            is_split_available,
+            player_hand,
+        );
+    }
+
+    let hand_sum = player_hand.hand.soft_total();
+    let mut player_actions = vec![PlayerAction::Hit];
+    // some tables make the player draw to a minimum total before standing
+    if hand_sum >= rules.min_stick_total {
+        player_actions.push(PlayerAction::Stay);
+    }
+
+    let is_double_allowed = match rules.double_on {
+        DoubleRule::AnyCards => true,
+        DoubleRule::AnyTwoCards => player_hand.hand.len() == 2,
+        DoubleRule::HardNineToEleven => {
+            player_hand.hand.len() == 2
+                && !player_hand.hand.is_soft()
+                && (9..=11).contains(&hand_sum)
+        }
+        DoubleRule::HardTenToEleven => {
+            player_hand.hand.len() == 2
+                && !player_hand.hand.is_soft()
+                && (10..=11).contains(&hand_sum)
+        }
+    } && !player_hand.previous_actions_taken.iter().any(|action| {
+        matches!(
+            action,
+            PlayerAction::DoubleDown | PlayerAction::DoubleForLess(_)
+        )
+    }) && (rules.double_after_split || !player_hand.is_split_hand);
+
+    if is_double_allowed
+        && rules.is_wager_allowed(player_hand.bet)
+        && (player_hand.bet <= player_working_balance || is_free_double(rules, player_hand))
+    {
+        player_actions.push(PlayerAction::DoubleDown);
+    }
+    // the most that can be put up within the table limits, anything less is taken too
+    let mut max_double_for_less = player_hand.bet.min(player_working_balance);
+    if let Some(bet_increment) = rules.bet_increment {
+        max_double_for_less = max_double_for_less.round_down_to(bet_increment);
+    }
+    // a double the house puts up costs nothing, so there is no less to double for
+    if is_double_allowed
+        && rules.double_for_less
+        && !is_free_double(rules, player_hand)
+        && rules.is_wager_allowed(max_double_for_less)
+    {
+        player_actions.push(PlayerAction::DoubleForLess(max_double_for_less));
+    }
+
+    if is_split_available {
+        player_actions.push(PlayerAction::Split);
+    }
+
+    // only the first decision of a starting hand, once any insurance is decided
+    if rules.surrender != Surrender::None
+        && player_hand.is_starting_hand
+        && player_hand.previous_actions_taken.iter().all(|action| {
+            matches!(
+                action,
+                PlayerAction::Insurance | PlayerAction::DeclineInsurance
+            )
+        })
+    {
+        player_actions.push(PlayerAction::Surrender);
+    }
+
+    player_actions
+}
+
+// pontoon twists and sticks in place of hitting and staying, and buys a card rather
+// than doubling: any time before twisting, and never for the fifth card
+fn get_pontoon_actions(
+    rules: &HouseRules,
+    player_working_balance: Chips,
+    is_split_available: bool,
+    player_hand: &PlayerHand,
+) -> Vec<PlayerAction> {
+    let mut player_actions = vec![PlayerAction::Twist];
+    if player_hand.hand.soft_total() >= rules.min_stick_total {
+        player_actions.push(PlayerAction::Stick);
+    }
+    if !player_hand
+        .previous_actions_taken
+        .contains(&PlayerAction::Twist)
+        && player_hand.hand.len() < 4
+        && rules.is_wager_allowed(get_pontoon_stake(player_hand))
+        && get_pontoon_stake(player_hand) <= player_working_balance
+    {
+        player_actions.push(PlayerAction::Buy);
+    }
+    if is_split_available {
+        player_actions.push(PlayerAction::Split);
+    }
+    player_actions
+}
+
+/// The stake a pontoon hand was dealt with, which each bought card adds to the bet again.
+pub fn get_pontoon_stake(player_hand: &PlayerHand) -> Chips {
+    let buy_count = player_hand
+        .previous_actions_taken
+        .iter()
+        .filter(|&&action| action == PlayerAction::Buy)
+        .count() as u32;
+    player_hand
+        .bet
+        .scale(1, buy_count + 1, PayoutRounding::HalfChip)
+}
+
+#[test]
+fn test_round_state_transitions() {
+    use crate::enums::{Rank, Suit};
+
+    // cards are dealt from the back of the deck
+    // player 10 8, dealer 9 7, dealer then draws K
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::King, Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten],
+        Suit::Spades,
+    );
+    let rules = HouseRules::default();
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+
+    assert_eq!(round.state(), RoundState::PlayerTurn { hand_index: 0 });
+    assert_eq!(round.player_working_balance(), Chips::new(90));
+    assert_eq!(
+        round.apply_action(PlayerAction::Split),
+        Err(RoundError::ActionNotAvailable(PlayerAction::Split))
+    );
+    assert_eq!(round.dealer_step(), Err(RoundError::NotDealerTurn));
+
+    assert_eq!(
+        round.apply_action(PlayerAction::Stay),
+        Ok(RoundState::DealerTurn)
+    );
+    assert_eq!(round.total_payout(), None);
+
+    assert_eq!(round.dealer_step(), Ok(DealerStep::Bust));
+    assert_eq!(round.state(), RoundState::Complete);
+    assert_eq!(round.total_payout(), Some(Chips::new(10)));
+
+    let events = round.take_events();
+    assert_eq!(
+        events[2],
+        GameEvent::CardDealt {
+            seat: Seat::Dealer,
+            card: None
+        }
+    );
+    assert!(events.contains(&GameEvent::ActionTaken {
+        hand_index: 0,
+        action: PlayerAction::Stay,
+        bet: Chips::new(10),
+        free_bet: Chips::ZERO
+    }));
+    assert_eq!(
+        events[events.len() - 2],
+        GameEvent::HandSettled {
+            hand_index: 0,
+            payout: Chips::new(10)
+        }
+    );
+    assert!(round.take_events().is_empty());
+}
+
+#[test]
+fn test_insurance_and_even_money() {
+    use crate::enums::Suit;
+
+    let rules = HouseRules {
+        insurance: true,
+        ..HouseRules::default()
+    };
+
+    // player 10 9 against a dealer blackjack, insured for half the bet
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Nine, Rank::King, Rank::Ten],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Insurance, PlayerAction::DeclineInsurance]
+    );
+    assert_eq!(round.total_payout(), None);
+    assert_eq!(
+        round.apply_action(PlayerAction::Insurance),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::ZERO));
+    assert!(round.take_events().contains(&GameEvent::InsuranceSettled {
+        payout: Chips::new(10)
+    }));
+
+    // an odd bet is insured for exactly half
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Nine, Rank::Seven, Rank::Ten],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(5));
+    round.apply_action(PlayerAction::Insurance).unwrap();
+    assert_eq!(round.player_working_balance(), Chips::from_half_chips(185));
+    assert_eq!(round.state(), RoundState::PlayerTurn { hand_index: 0 });
+
+    // a player blackjack is offered even money, paid whatever the hole card
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::King, Rank::Seven, Rank::Ace],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::Insurance),
+        Err(RoundError::ActionNotAvailable(PlayerAction::Insurance))
+    );
+    assert_eq!(
+        round.apply_action(PlayerAction::EvenMoney),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(10)));
+
+    // without the rule there is no insurance decision
+    let rules = HouseRules::default();
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::King, Rank::Seven, Rank::Ace],
+        Suit::Hearts,
+    );
+    let round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.total_payout(), Some(Chips::new(15)));
+}
+
+#[test]
+fn test_late_and_early_surrender() {
+    use crate::enums::Suit;
+    // player 10 6 against a dealer ace with a king in the hole
+    let dealer_blackjack = [Rank::Ace, Rank::Six, Rank::King, Rank::Ten];
+
+    // late surrender comes after the peek, so the dealer blackjack settles first
+    let late_rules = HouseRules {
+        surrender: Surrender::Late,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(&dealer_blackjack, Suit::Clubs);
+    let round = Round::deal(&mut shoe, &late_rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Nine, Rank::Six, Rank::Seven, Rank::Ten],
+        Suit::Clubs,
+    );
+    let mut round = Round::deal(&mut shoe, &late_rules, Chips::new(100), Chips::new(5));
+    assert!(round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Surrender));
+    assert_eq!(
+        round.apply_action(PlayerAction::Surrender),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::from_half_chips(-5)));
+
+    // early surrender is decided before the peek and still saves half the bet
+    let early_rules = HouseRules {
+        surrender: Surrender::Early,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(&dealer_blackjack, Suit::Clubs);
+    let mut round = Round::deal(&mut shoe, &early_rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.state(), RoundState::PlayerTurn { hand_index: 0 });
+    assert_eq!(
+        round.apply_action(PlayerAction::Surrender),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(-5)));
+
+    let mut shoe = Shoe::from_ranks(&dealer_blackjack, Suit::Clubs);
+    let mut round = Round::deal(&mut shoe, &early_rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.player().hands[0].hand.len(), 2);
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    // surrender is only offered as the first decision
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Two, Rank::Nine, Rank::Six, Rank::Seven, Rank::Two],
+        Suit::Clubs,
+    );
+    let mut round = Round::deal(&mut shoe, &early_rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Hit).unwrap();
+    assert!(!round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Surrender));
+}
+
+#[test]
+fn test_no_hole_card_dealer_blackjack() {
+    use crate::enums::Suit;
+    // player 8 8 against a dealer ten, split and doubled both hands,
+    // the dealer then draws an ace for blackjack
+    let ranks = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Three,
+        Rank::Two,
+        Rank::Eight,
+        Rank::Ten,
+        Rank::Eight,
+    ];
+    let play = |rules: &HouseRules| {
+        let mut shoe = Shoe::from_ranks(&ranks, Suit::Diamonds);
+        let mut round = Round::deal(&mut shoe, rules, Chips::new(100), Chips::new(10));
+        assert_eq!(round.dealer_hand().len(), 1);
+        assert_eq!(round.dealer_upcard().rank, Rank::Ten);
+        assert_eq!(round.dealer_hole_card(), None);
+        assert_eq!(round.table_snapshot(true).dealer_cards.len(), 1);
+        assert!(round.table_snapshot(true).dealer_cards[0].is_some());
+
+        round.apply_action(PlayerAction::Split).unwrap();
+        round.apply_action(PlayerAction::DoubleDown).unwrap();
+        round.apply_action(PlayerAction::DoubleDown).unwrap();
+        assert_eq!(round.state(), RoundState::Complete);
+        assert!(round.is_dealer_blackjack());
+        round.total_payout().unwrap()
+    };
+
+    let rules = HouseRules {
+        hole_card: HoleCardRule::NoHoleCard,
+        ..HouseRules::default()
+    };
+    assert_eq!(play(&rules), Chips::new(-40));
+
+    let rules = HouseRules {
+        hole_card: HoleCardRule::NoHoleCardOriginalBetOnly,
+        ..HouseRules::default()
+    };
+    assert_eq!(play(&rules), Chips::new(-10));
+
+    // a hand settled on sight by a hand rule still loses to the dealer's blackjack
+    let charlie_rules = HouseRules {
+        hand_rules: vec![HandRule::CardCharlie { cards: 3 }],
+        ..rules.clone()
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Four, Rank::Three, Rank::Ten, Rank::Two],
+        Suit::Diamonds,
+    );
+    let mut round = Round::deal(&mut shoe, &charlie_rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.dealer_hand().len(), 2);
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    // and is paid once the dealer's second card shows there is none
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Seven, Rank::Four, Rank::Three, Rank::Ten, Rank::Two],
+        Suit::Diamonds,
+    );
+    let mut round = Round::deal(&mut shoe, &charlie_rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Hit).unwrap();
+    assert_eq!(round.dealer_hand().len(), 2);
+    assert_eq!(round.total_payout(), Some(Chips::new(10)));
+
+    // two hands dealt at a switch table each lose their own original bet
+    let switch_rules = HouseRules {
+        switch_hands: true,
+        ..rules.clone()
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Ace,
+            Rank::Three,
+            Rank::Two,
+            Rank::Five,
+            Rank::Six,
+            Rank::Ten,
+            Rank::Five,
+            Rank::Five,
+        ],
+        Suit::Diamonds,
+    );
+    let mut round = Round::deal(&mut shoe, &switch_rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::DoubleDown).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::DoubleDown),
+        Ok(RoundState::Complete)
+    );
+    assert!(round.is_dealer_blackjack());
+    assert_eq!(round.total_payout(), Some(Chips::new(-20)));
+
+    // a hand that busted before the dealer's blackjack has already lost all of its bet
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Ace,
+            Rank::King,
+            Rank::Five,
+            Rank::Three,
+            Rank::Eight,
+            Rank::Ten,
+            Rank::Eight,
+        ],
+        Suit::Diamonds,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    round.apply_action(PlayerAction::Stay).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert!(round.is_dealer_blackjack());
+    assert_eq!(round.player().hands[0].payout, Some(Chips::new(-10)));
+    assert_eq!(round.player().hands[1].payout, Some(Chips::new(-10)));
+
+    // a surrender is decided before the dealer's second card, so it can only be early
+    // and saves half the bet whatever the dealer draws
+    let late_rules = HouseRules {
+        surrender: Surrender::Late,
+        ..rules.clone()
+    };
+    assert!(late_rules.validate().is_err());
+    let early_rules = HouseRules {
+        surrender: Surrender::Early,
+        ..rules.clone()
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Six, Rank::Ten, Rank::Ten],
+        Suit::Diamonds,
+    );
+    let mut round = Round::deal(&mut shoe, &early_rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::Surrender),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(-5)));
+
+    // a player natural waits for the dealer's second card before it is paid
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Seven, Rank::King, Rank::Nine, Rank::Ace],
+        Suit::Diamonds,
+    );
+    let round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.state(), RoundState::Complete);
+    assert_eq!(round.dealer_hand().len(), 2);
+    assert_eq!(round.total_payout(), Some(Chips::new(15)));
+}
+
+#[test]
+fn test_split_aces_and_split_21() {
+    use crate::enums::Suit;
+    // player A A against a dealer 10 7, the split aces draw a king and an ace
+    let ranks = [
+        Rank::Ace,
+        Rank::King,
+        Rank::Seven,
+        Rank::Ace,
+        Rank::Ten,
+        Rank::Ace,
+    ];
+
+    let rules = HouseRules::default();
+    let mut shoe = Shoe::from_ranks(&ranks, Suit::Hearts);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    // both aces have their one card and the second pair of aces may not be split again
+    assert_eq!(round.state(), RoundState::DealerTurn);
+    round.dealer_step().unwrap();
+    // the A K after the split is 21, not blackjack, and A A is a soft 12
+    assert_eq!(round.total_payout(), Some(Chips::ZERO));
+
+    let rules = HouseRules {
+        resplit_aces: true,
+        blackjack_after_split: true,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(&ranks, Suit::Hearts);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    assert_eq!(round.state(), RoundState::PlayerTurn { hand_index: 1 });
+    assert_eq!(
+        round.player().hands[1].avaiable_actions,
+        vec![PlayerAction::Stay, PlayerAction::Split]
+    );
+    round.apply_action(PlayerAction::Stay).unwrap();
+    round.dealer_step().unwrap();
+    assert_eq!(round.total_payout(), Some(Chips::new(15 - 10)));
+
+    // a split blackjack beats a dealer 21 that took three cards
+    let rules = HouseRules {
+        blackjack_after_split: true,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::King,
+            Rank::Nine,
+            Rank::King,
+            Rank::Five,
+            Rank::Ace,
+            Rank::Six,
+            Rank::Ace,
+        ],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    while round.state() == RoundState::DealerTurn {
+        round.dealer_step().unwrap();
+    }
+    assert_eq!(round.dealer_hand().soft_total(), 21);
+    assert_eq!(round.player().hands[0].payout, Some(Chips::new(15)));
+    assert_eq!(round.total_payout(), Some(Chips::new(15 - 10)));
+
+    // without the one card rule split aces play on like any other hand,
+    // and the hand that was split may no longer double without double after split
+    let rules = HouseRules {
+        split_aces_one_card: false,
+        double_after_split: false,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(&ranks, Suit::Hearts);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    assert_eq!(round.state(), RoundState::PlayerTurn { hand_index: 0 });
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Hit, PlayerAction::Stay]
+    );
+}
+
+#[test]
+fn test_max_hands_per_seat() {
+    use crate::enums::Suit;
+
+    // player 8 8 against a dealer 10 7, and every split draws another 8 until a 2
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Two,
+            Rank::Eight,
+            Rank::Eight,
+            Rank::Eight,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Ten,
+            Rank::Eight,
+        ],
+        Suit::Clubs,
+    );
+    let rules = HouseRules {
+        max_hands_per_seat: 3,
+        ..HouseRules::default()
+    };
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+
+    round.apply_action(PlayerAction::Split).unwrap();
+    assert!(round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Split));
+    round.apply_action(PlayerAction::Split).unwrap();
+
+    // the resplit hand sits next to the hand it came from
+    let hand_totals: Vec<u8> = round
+        .player()
+        .hands
+        .iter()
+        .map(|hand| hand.hand.soft_total())
+        .collect();
+    assert_eq!(hand_totals, vec![16, 10, 16]);
+    assert!(!round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Split));
+    assert_eq!(round.player_working_balance(), Chips::new(70));
+}
+
+#[test]
+fn test_double_restrictions() {
+    use crate::enums::Suit;
+
+    let hand_of = |ranks: &[Rank]| {
+        PlayerHand::new(
+            Hand::from_cards(
+                ranks
+                    .iter()
+                    .map(|&rank| Card::new(rank, Suit::Spades))
+                    .collect(),
+            ),
+            Chips::new(10),
+        )
+    };
+    let can_double = |rules: &HouseRules, hand: &PlayerHand| {
+        get_player_actions(rules, Chips::new(100), 1, hand).contains(&PlayerAction::DoubleDown)
+    };
+    let nine = hand_of(&[Rank::Five, Rank::Four]);
+    // a hard 9 only when the ace counts as 1
+    let ace_eight = hand_of(&[Rank::Ace, Rank::Eight]);
+    let soft_seventeen = hand_of(&[Rank::Ace, Rank::Six]);
+    let hit_eleven = hand_of(&[Rank::Two, Rank::Four, Rank::Five]);
+
+    let rules = HouseRules::default();
+    assert!(can_double(&rules, &nine));
+    assert!(can_double(&rules, &soft_seventeen));
+    assert!(!can_double(&rules, &hit_eleven));
+
+    let rules = HouseRules {
+        double_on: DoubleRule::HardNineToEleven,
+        ..HouseRules::default()
+    };
+    assert!(can_double(&rules, &nine));
+    assert!(!can_double(&rules, &soft_seventeen));
+    assert!(!can_double(&rules, &ace_eight));
+
+    let rules = HouseRules {
+        double_on: DoubleRule::HardTenToEleven,
+        ..HouseRules::default()
+    };
+    assert!(!can_double(&rules, &nine));
+
+    let rules = HouseRules {
+        double_on: DoubleRule::AnyCards,
+        ..HouseRules::default()
+    };
+    assert!(can_double(&rules, &hit_eleven));
+
+    // doubling for less with only 4 chips left after a 10 chip bet
+    let rules = HouseRules {
+        double_for_less: true,
+        ..HouseRules::default()
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Nine, Rank::Seven, Rank::Six, Rank::Ten, Rank::Five],
+        Suit::Spades,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(14), Chips::new(10));
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![
+            PlayerAction::Hit,
+            PlayerAction::Stay,
+            PlayerAction::DoubleForLess(Chips::new(4))
+        ]
+    );
+    assert_eq!(
+        round.apply_action(PlayerAction::DoubleForLess(Chips::new(5))),
+        Err(RoundError::ActionNotAvailable(PlayerAction::DoubleForLess(
+            Chips::new(5)
+        )))
+    );
+    round
+        .apply_action(PlayerAction::DoubleForLess(Chips::new(3)))
+        .unwrap();
+    assert_eq!(round.player().hands[0].bet, Chips::new(13));
+    assert_eq!(round.player_working_balance(), Chips::new(1));
+    round.dealer_step().unwrap();
+    assert_eq!(round.total_payout(), Some(Chips::new(13)));
+}
+
+#[test]
+fn test_card_charlie() {
+    use crate::enums::Suit;
+
+    // player 2 3 against a dealer 10 10, hitting 2 3 4 for a five card 14
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Four,
+            Rank::Three,
+            Rank::Two,
+            Rank::Ten,
+            Rank::Three,
+            Rank::Ten,
+            Rank::Two,
+        ],
+        Suit::Hearts,
+    );
+    let rules = HouseRules {
+        hand_rules: vec![HandRule::CardCharlie { cards: 5 }],
+        ..HouseRules::default()
+    };
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Hit).unwrap();
+    round.apply_action(PlayerAction::Hit).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(10)));
+    assert!(round.take_events().contains(&GameEvent::HandRuleApplied {
+        hand_index: 0,
+        rule: HandRule::CardCharlie { cards: 5 },
+        payout: Chips::new(10)
+    }));
+}
+
+#[test]
+fn test_spanish_21_bonuses_and_rescue() {
+    use crate::enums::{GameVariant, Suit};
+
+    let rules = HouseRules::for_variant(GameVariant::Spanish21);
+
+    // player 6 7 against a dealer K 9, hitting a suited 8 for 6-7-8
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Eight, Rank::Nine, Rank::Seven, Rank::King, Rank::Six],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(20)));
+
+    // player 2 3 hitting 4 5 7 for a five card 21
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Seven,
+            Rank::Five,
+            Rank::Four,
+            Rank::Nine,
+            Rank::Three,
+            Rank::King,
+            Rank::Two,
+        ],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Hit).unwrap();
+    round.apply_action(PlayerAction::Hit).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::Hit),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(15)));
+
+    // player 5 6 doubles into a 2 for 13 and takes the double back
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Two, Rank::Nine, Rank::Six, Rank::King, Rank::Five],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::DoubleDown),
+        Ok(RoundState::PlayerTurn { hand_index: 0 })
+    );
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Stay, PlayerAction::Rescue]
+    );
+    assert_eq!(
+        round.apply_action(PlayerAction::Rescue),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    // a doubled 6-7-8 wins even money on the doubled bet but no bonus
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Eight, Rank::Nine, Rank::Seven, Rank::King, Rank::Six],
+        Suit::Hearts,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(
+        round.apply_action(PlayerAction::DoubleDown),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(20)));
+}
+
+#[test]
+fn test_blackjack_switch() {
+    use crate::enums::{GameVariant, Suit};
+    use crate::game::play_session;
+    use crate::input::ScriptedInput;
+    use crate::render::SilentRenderer;
+
+    // hands K 5 and 6 K switch into K K and 6 5, and the dealer's 6 6 draws a K to 22
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::King,
+            Rank::Six,
+            Rank::King,
+            Rank::Five,
+            Rank::Six,
+            Rank::Six,
+            Rank::King,
+        ],
+        Suit::Clubs,
+    );
+    let rules = HouseRules::for_variant(GameVariant::BlackjackSwitch);
+    assert!(!rules.is_bet_allowed(Chips::new(15), Chips::new(10)));
+    assert_eq!(rules.min_round_stake(), Chips::new(2));
+
+    // a balance that only covers one of the two bets is not dealt a round
+    let mut scripted_input = ScriptedInput::new(vec![Chips::new(1)], vec![]);
+    let outcome = play_session(
+        &rules,
+        1,
+        Chips::new(1),
+        &mut scripted_input,
+        &mut SilentRenderer,
+        &mut |_, _, _| {},
+    )
+    .unwrap();
+    assert!(outcome.record.rounds.is_empty());
+
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.player_working_balance(), Chips::new(80));
+    assert!(round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Switch));
+    assert_eq!(
+        round.apply_action(PlayerAction::Switch),
+        Ok(RoundState::PlayerTurn { hand_index: 0 })
+    );
+    assert_eq!(round.player().hands[0].hand.soft_total(), 20);
+    assert_eq!(round.player().hands[1].hand.soft_total(), 11);
+    assert!(!round.player().hands[0]
+        .avaiable_actions
+        .contains(&PlayerAction::Switch));
+
+    round.apply_action(PlayerAction::Stay).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::Stay),
+        Ok(RoundState::DealerTurn)
+    );
+    while round.state() == RoundState::DealerTurn {
+        round.dealer_step().unwrap();
+    }
+    assert_eq!(round.dealer_hand().soft_total(), 22);
+    assert_eq!(round.total_payout(), Some(Chips::ZERO));
+
+    // hands A 5 and 6 7 switch into A 7 and 6 5, and a switched ace is no split ace
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Ten,
+            Rank::Nine,
+            Rank::Seven,
+            Rank::Five,
+            Rank::Ten,
+            Rank::Six,
+            Rank::Ace,
+        ],
+        Suit::Clubs,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Switch).unwrap();
+    assert_eq!(round.player().hands[0].hand.soft_total(), 18);
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![
+            PlayerAction::Hit,
+            PlayerAction::Stay,
+            PlayerAction::DoubleDown
+        ]
+    );
+    round.apply_action(PlayerAction::Stay).unwrap();
+    round.apply_action(PlayerAction::DoubleDown).unwrap();
+    round.dealer_step().unwrap();
+    // 18 loses to the dealer's 19 and the doubled 21 wins
+    assert_eq!(round.total_payout(), Some(Chips::new(10)));
+
+    // insurance covers both hands against a dealer ace
+    let rules = HouseRules {
+        insurance: true,
+        ..rules
+    };
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Ace,
+            Rank::Nine,
+            Rank::Eight,
+            Rank::Seven,
+            Rank::Ten,
+            Rank::Ten,
+        ],
+        Suit::Clubs,
+    );
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert!(round.take_events().contains(&GameEvent::InsuranceOffered {
+        cost: Chips::new(10),
+        is_even_money: false
+    }));
+    round.apply_action(PlayerAction::Insurance).unwrap();
+    assert_eq!(round.player_working_balance(), Chips::new(70));
+}
+
+#[test]
+fn test_double_exposure() {
+    use crate::enums::{GameVariant, Suit};
+
+    // player 10 8 against a dealer 10 8 that stands
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Eight, Rank::Eight, Rank::Ten, Rank::Ten],
+        Suit::Diamonds,
+    );
+    let rules = HouseRules::for_variant(GameVariant::DoubleExposure);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert!(round
+        .table_snapshot(true)
+        .dealer_cards
+        .iter()
+        .all(|card| card.is_some()));
+
+    assert_eq!(
+        round.apply_action(PlayerAction::Stay),
+        Ok(RoundState::DealerTurn)
+    );
+    round.dealer_step().unwrap();
+    // the tie goes to the dealer
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    // but tied blackjacks still push
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Ace, Rank::King, Rank::King],
+        Suit::Diamonds,
+    );
+    let round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.total_payout(), Some(Chips::ZERO));
+}
+
+#[test]
+fn test_free_bet_doubles_and_splits() {
+    use crate::enums::{GameVariant, Suit};
+
+    // player 8 8 splits into 8 3, which doubles into a 2, and 8 10, against a dealer K 10
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Two,
+            Rank::Ten,
+            Rank::Three,
+            Rank::Ten,
+            Rank::Eight,
+            Rank::King,
+            Rank::Eight,
+        ],
+        Suit::Spades,
+    );
+    let rules = HouseRules::for_variant(GameVariant::FreeBet);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    round.apply_action(PlayerAction::Split).unwrap();
+    round.apply_action(PlayerAction::DoubleDown).unwrap();
+    assert_eq!(
+        round.apply_action(PlayerAction::Stay),
+        Ok(RoundState::DealerTurn)
+    );
+    // the split and the double were both on the house
+    assert_eq!(round.player_working_balance(), Chips::new(90));
+    assert_eq!(round.player().hands[0].free_bet, Chips::new(10));
+    assert_eq!(round.player().hands[1].free_bet, Chips::new(10));
+
+    round.dealer_step().unwrap();
+    // both hands lose, but only the bet the player put up
+    assert_eq!(round.player().hands[0].payout, Some(Chips::new(-10)));
+    assert_eq!(round.player().hands[1].payout, Some(Chips::ZERO));
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+
+    let tens = PlayerHand::new(
+        Hand::from_cards(vec![
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Queen, Suit::Spades),
+        ]),
+        Chips::new(10),
+    );
+    assert!(!is_free_split(&rules, &tens));
+
+    // a free double is not offered for less, which the player would pay for
+    let rules = HouseRules {
+        double_for_less: true,
+        ..rules
+    };
+    let nine = PlayerHand::new(
+        Hand::from_cards(vec![
+            Card::new(Rank::Five, Suit::Spades),
+            Card::new(Rank::Four, Suit::Spades),
+        ]),
+        Chips::new(10),
+    );
+    assert_eq!(
+        get_player_actions(&rules, Chips::new(100), 1, &nine),
+        vec![
+            PlayerAction::Hit,
+            PlayerAction::Stay,
+            PlayerAction::DoubleDown
+        ]
+    );
+    assert!(get_player_actions(&rules, Chips::new(100), 1, &tens)
+        .contains(&PlayerAction::DoubleForLess(Chips::new(10))));
+}
+
+#[test]
+fn test_pontoon_actions_and_five_card_trick() {
+    use crate::enums::{GameVariant, Suit};
+
+    // player 5 6 against a dealer K 9 buys a 2, then twists a 2 and a 3 for a five card 18
+    let mut shoe = Shoe::from_ranks(
+        &[
+            Rank::Three,
+            Rank::Two,
+            Rank::Two,
+            Rank::Nine,
+            Rank::Six,
+            Rank::King,
+            Rank::Five,
+        ],
+        Suit::Hearts,
+    );
+    let rules = HouseRules::for_variant(GameVariant::Pontoon);
+    let mut round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    // no sticking below 15
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Twist, PlayerAction::Buy]
+    );
+
+    round.apply_action(PlayerAction::Buy).unwrap();
+    assert_eq!(round.player().hands[0].bet, Chips::new(20));
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Twist, PlayerAction::Buy]
+    );
+
+    // once a hand twists it can no longer buy
+    round.apply_action(PlayerAction::Twist).unwrap();
+    assert_eq!(
+        round.player().hands[0].avaiable_actions,
+        vec![PlayerAction::Twist, PlayerAction::Stick]
+    );
+    assert_eq!(
+        round.apply_action(PlayerAction::Twist),
+        Ok(RoundState::Complete)
+    );
+    assert_eq!(round.total_payout(), Some(Chips::new(40)));
+
+    // a bought card stakes the hand again, which has to be a bet the table takes
+    let limited_rules = HouseRules {
+        min_bet: Chips::new(10),
+        bet_increment: Some(Chips::new(10)),
+        ..rules.clone()
+    };
+    let five_six = PlayerHand::new(
+        Hand::from_cards(vec![
+            Card::new(Rank::Five, Suit::Hearts),
+            Card::new(Rank::Six, Suit::Hearts),
+        ]),
+        Chips::new(15),
+    );
+    assert_eq!(
+        get_player_actions(&limited_rules, Chips::new(100), 1, &five_six),
+        vec![PlayerAction::Twist]
+    );
+
+    // the dealer's pontoon beats the player's
+    let mut shoe = Shoe::from_ranks(
+        &[Rank::Ace, Rank::Ace, Rank::King, Rank::King],
+        Suit::Hearts,
+    );
+    let round = Round::deal(&mut shoe, &rules, Chips::new(100), Chips::new(10));
+    assert_eq!(round.state(), RoundState::Complete);
+    assert_eq!(round.total_payout(), Some(Chips::new(-10)));
+}
+
+#[test]
+fn test_side_bets_settle_on_the_opening_cards() {
+    use crate::enums::Suit;
+    use crate::side_bets::{PerfectPairs, SideBetRule, TwentyOnePlusThree};
+
+    // player 8♥ 8♦ against a dealer 10 9
+    let mut shoe = Shoe::from_cards(vec![
+        Card::new(Rank::Nine, Suit::Clubs),
+        Card::new(Rank::Eight, Suit::Diamonds),
+        Card::new(Rank::Ten, Suit::Clubs),
+        Card::new(Rank::Eight, Suit::Hearts),
+    ]);
+    let rules = HouseRules {
+        side_bets: vec![
+            SideBetRule::TwentyOnePlusThree(TwentyOnePlusThree::default()),
+            SideBetRule::PerfectPairs(PerfectPairs::default()),
+        ],
+        ..HouseRules::default()
+    };
+    let mut round = Round::deal_with_side_bets(
+        &mut shoe,
+        &rules,
+        Chips::new(100),
+        Chips::new(10),
+        &[Chips::new(1), Chips::new(2)],
+    );
+    // the colored pair pays 12:1 straight away, and 8 8 9 makes nothing for 21+3
+    let payouts: Vec<Chips> = round
+        .side_bet_results()
+        .iter()
+        .map(|result| result.payout)
+        .collect();
+    assert_eq!(payouts, vec![Chips::new(-1), Chips::new(24)]);
+    assert_eq!(round.player_working_balance(), Chips::new(113));
+
+    round.apply_action(PlayerAction::Stay).unwrap();
+    round.dealer_step().unwrap();
+    assert_eq!(round.total_payout(), Some(Chips::new(13)));
+}
+
+#[test]
+fn test_table_limits_apply_to_doubles_and_splits() {
+    use crate::enums::Suit;
+
+    let rules = HouseRules {
+        double_for_less: true,
+        min_bet: Chips::new(10),
+        bet_increment: Some(Chips::new(5)),
+        ..HouseRules::default()
+    };
+    let pair_of_eights = |bet: Chips| {
+        PlayerHand::new(
+            Hand::from_cards(vec![
+                Card::new(Rank::Eight, Suit::Clubs),
+                Card::new(Rank::Eight, Suit::Hearts),
+            ]),
+            bet,
+        )
+    };
+
+    // 7 chips left only covers a 5 chip double, which is under the table minimum
+    let player_actions =
+        get_player_actions(&rules, Chips::new(7), 1, &pair_of_eights(Chips::new(10)));
+    assert_eq!(player_actions, vec![PlayerAction::Hit, PlayerAction::Stay]);
+
+    let player_actions =
+        get_player_actions(&rules, Chips::new(100), 1, &pair_of_eights(Chips::new(20)));
+    assert!(player_actions.contains(&PlayerAction::DoubleForLess(Chips::new(20))));
+    assert!(player_actions.contains(&PlayerAction::Split));
+    assert!(is_action_available(
+        &rules,
+        &player_actions,
+        PlayerAction::DoubleForLess(Chips::new(15))
+    ));
+    assert!(!is_action_available(
+        &rules,
+        &player_actions,
+        PlayerAction::DoubleForLess(Chips::new(12))
+    ));
+    assert!(!is_action_available(
+        &rules,
+        &player_actions,
+        PlayerAction::DoubleForLess(Chips::new(5))
+    ));
+}
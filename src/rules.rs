@@ -0,0 +1,591 @@
+use crate::chips::Chips;
+use crate::deck::SHOE_DECK_COUNTS;
+use crate::enums::{
+    DoubleRule, GameVariant, HandRule, HoleCardRule, PayoutRounding, Rank, RulesError, Suit,
+    Surrender,
+};
+use crate::hand::Hand;
+use crate::models::Card;
+use crate::side_bets::SideBetRule;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// The table rules a round is played under.
+///
+/// Every field has a default so a rules file only needs to list what it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HouseRules {
+    pub starting_balance: Chips,
+    pub deck_count: u8,
+    pub removed_ranks: Vec<Rank>,
+    pub penetration_percent: u8,
+    pub dealer_hits_soft_17: bool,
+    pub dealer_22_pushes: bool,
+    pub dealer_cards_exposed: bool,
+    pub dealer_wins_ties: bool,
+    pub dealer_wins_tied_naturals: bool,
+    pub hole_card: HoleCardRule,
+    pub blackjack_payout: PayoutRatio,
+    pub payout_rounding: PayoutRounding,
+    pub min_stick_total: u8,
+    pub pontoon_actions: bool,
+    pub double_on: DoubleRule,
+    pub double_for_less: bool,
+    pub double_down_rescue: bool,
+    pub free_doubles: bool,
+    pub free_splits: bool,
+    pub double_after_split: bool,
+    pub allow_resplit: bool,
+    pub max_hands_per_seat: u8,
+    pub split_aces_one_card: bool,
+    pub resplit_aces: bool,
+    pub blackjack_after_split: bool,
+    pub surrender: Surrender,
+    pub switch_hands: bool,
+    pub hand_rules: Vec<HandRule>,
+    pub side_bets: Vec<SideBetRule>,
+    pub insurance: bool,
+    pub min_bet: Chips,
+    pub max_bet: Option<Chips>,
+    pub bet_increment: Option<Chips>,
+}
+
+impl Default for HouseRules {
+    fn default() -> Self {
+        HouseRules {
+            starting_balance: Chips::new(500),
+            deck_count: 6,
+            removed_ranks: vec![],
+            penetration_percent: 75,
+            dealer_hits_soft_17: false,
+            dealer_22_pushes: false,
+            dealer_cards_exposed: false,
+            dealer_wins_ties: false,
+            dealer_wins_tied_naturals: false,
+            hole_card: HoleCardRule::Peek,
+            blackjack_payout: PayoutRatio::new(3, 2),
+            payout_rounding: PayoutRounding::HalfChip,
+            min_stick_total: 0,
+            pontoon_actions: false,
+            double_on: DoubleRule::AnyTwoCards,
+            double_for_less: false,
+            double_down_rescue: false,
+            free_doubles: false,
+            free_splits: false,
+            // fun house rules
+            double_after_split: true,
+            allow_resplit: true,
+            max_hands_per_seat: 4,
+            split_aces_one_card: true,
+            resplit_aces: false,
+            blackjack_after_split: false,
+            surrender: Surrender::None,
+            switch_hands: false,
+            hand_rules: vec![],
+            side_bets: vec![],
+            insurance: false,
+            min_bet: Chips::new(1),
+            max_bet: None,
+            bet_increment: None,
+        }
+    }
+}
+
+impl HouseRules {
+    /// The house rules `variant` is played with.
+    pub fn for_variant(variant: GameVariant) -> Self {
+        match variant {
+            GameVariant::Classic => HouseRules::default(),
+            GameVariant::Spanish21 => HouseRules {
+                removed_ranks: vec![Rank::Ten],
+                dealer_hits_soft_17: true,
+                double_on: DoubleRule::AnyCards,
+                double_down_rescue: true,
+                resplit_aces: true,
+                surrender: Surrender::Late,
+                // the bigger bonuses come first so they win over a plain 21
+                hand_rules: vec![
+                    HandRule::CardCountTwentyOne {
+                        cards: 7,
+                        payout: PayoutRatio::new(3, 1),
+                    },
+                    HandRule::CardCountTwentyOne {
+                        cards: 6,
+                        payout: PayoutRatio::new(2, 1),
+                    },
+                    HandRule::CardCountTwentyOne {
+                        cards: 5,
+                        payout: PayoutRatio::new(3, 2),
+                    },
+                    HandRule::ThreeCardTwentyOne {
+                        ranks: [Rank::Six, Rank::Seven, Rank::Eight],
+                        mixed: PayoutRatio::new(3, 2),
+                        suited: PayoutRatio::new(2, 1),
+                        spades: PayoutRatio::new(3, 1),
+                    },
+                    HandRule::ThreeCardTwentyOne {
+                        ranks: [Rank::Seven, Rank::Seven, Rank::Seven],
+                        mixed: PayoutRatio::new(3, 2),
+                        suited: PayoutRatio::new(2, 1),
+                        spades: PayoutRatio::new(3, 1),
+                    },
+                    HandRule::TwentyOneWins,
+                ],
+                ..HouseRules::default()
+            },
+            GameVariant::BlackjackSwitch => HouseRules {
+                dealer_hits_soft_17: true,
+                dealer_22_pushes: true,
+                blackjack_payout: PayoutRatio::new(1, 1),
+                switch_hands: true,
+                ..HouseRules::default()
+            },
+            GameVariant::DoubleExposure => HouseRules {
+                dealer_hits_soft_17: true,
+                dealer_cards_exposed: true,
+                dealer_wins_ties: true,
+                blackjack_payout: PayoutRatio::new(1, 1),
+                ..HouseRules::default()
+            },
+            GameVariant::FreeBet => HouseRules {
+                dealer_hits_soft_17: true,
+                dealer_22_pushes: true,
+                free_doubles: true,
+                free_splits: true,
+                ..HouseRules::default()
+            },
+            GameVariant::Pontoon => HouseRules {
+                dealer_hits_soft_17: true,
+                dealer_wins_ties: true,
+                dealer_wins_tied_naturals: true,
+                blackjack_payout: PayoutRatio::new(2, 1),
+                min_stick_total: 15,
+                pontoon_actions: true,
+                split_aces_one_card: false,
+                hand_rules: vec![HandRule::CardTrick {
+                    cards: 5,
+                    payout: PayoutRatio::new(2, 1),
+                }],
+                ..HouseRules::default()
+            },
+        }
+    }
+
+    /// How many hands the player is dealt, each with the full bet on it.
+    pub fn starting_hand_count(&self) -> usize {
+        if self.switch_hands {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// The least a round can be played for: the table minimum on every hand dealt.
+    pub fn min_round_stake(&self) -> Chips {
+        self.min_bet * self.starting_hand_count() as i64
+    }
+
+    /// Loads rules from a `.json` or `.toml` file, chosen by the file extension.
+    pub fn load_from_file(path: &Path) -> Result<Self, RulesError> {
+        HouseRules::load_from_file_over(&HouseRules::default(), path)
+    }
+
+    /// Loads a rules file on top of `base`, so the file only lists what it changes from it.
+    pub fn load_from_file_over(base: &HouseRules, path: &Path) -> Result<Self, RulesError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| RulesError::Io(path.display().to_string(), err))?;
+
+        let overrides: serde_json::Value = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => {
+                serde_json::from_str(&contents).map_err(|err| RulesError::Parse(err.to_string()))?
+            }
+            Some("toml") => {
+                toml::from_str(&contents).map_err(|err| RulesError::Parse(err.to_string()))?
+            }
+            _ => return Err(RulesError::UnsupportedFormat(path.display().to_string())),
+        };
+
+        let mut rules =
+            serde_json::to_value(base).expect("Error: Failed to serialize house rules.");
+        match (rules.as_object_mut(), overrides) {
+            (Some(rules), serde_json::Value::Object(overrides)) => rules.extend(overrides),
+            _ => {
+                return Err(RulesError::Parse(
+                    "expected a table of rule settings".to_string(),
+                ))
+            }
+        }
+        let rules: HouseRules =
+            serde_json::from_value(rules).map_err(|err| RulesError::Parse(err.to_string()))?;
+
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Whether `bet` can go on every starting hand out of `balance` within the table limits.
+    pub fn is_bet_allowed(&self, balance: Chips, bet: Chips) -> bool {
+        bet.checked_mul(self.starting_hand_count() as i64)
+            .is_some_and(|stake| stake <= balance)
+            && self.is_wager_allowed(bet)
+    }
+
+    /// Whether a single wager, a bet or the chips put up to double or split, is within
+    /// the table minimum and maximum and a whole number of bet increments.
+    pub fn is_wager_allowed(&self, amount: Chips) -> bool {
+        amount >= self.min_bet
+            && self.max_bet.is_none_or(|max_bet| amount <= max_bet)
+            && self
+                .bet_increment
+                .is_none_or(|bet_increment| amount.is_multiple_of(bet_increment))
+    }
+
+    /// Whether `side_bets`, one amount for each of the table's side bets, fit in what is
+    /// left of `balance` once `bet` is on every starting hand.
+    pub fn are_side_bets_allowed(&self, balance: Chips, bet: Chips, side_bets: &[Chips]) -> bool {
+        let balance_after_bet = balance - bet * self.starting_hand_count() as i64;
+        side_bets.len() <= self.side_bets.len()
+            && side_bets.iter().all(|&side_bet| side_bet >= Chips::ZERO)
+            && side_bets.iter().copied().sum::<Chips>() <= balance_after_bet
+    }
+
+    pub fn validate(&self) -> Result<(), RulesError> {
+        if !SHOE_DECK_COUNTS.contains(&self.deck_count) {
+            return Err(RulesError::Invalid(format!(
+                "deck_count must be one of {:?}",
+                SHOE_DECK_COUNTS
+            )));
+        }
+        if !(1..=100).contains(&self.penetration_percent) {
+            return Err(RulesError::Invalid(
+                "penetration_percent must be between 1 and 100".to_string(),
+            ));
+        }
+        if Rank::ALL
+            .iter()
+            .all(|rank| self.removed_ranks.contains(rank))
+        {
+            return Err(RulesError::Invalid(
+                "removed_ranks must leave at least one rank in the shoe".to_string(),
+            ));
+        }
+        if self.blackjack_payout.numerator == 0 || self.blackjack_payout.denominator == 0 {
+            return Err(RulesError::Invalid(
+                "blackjack_payout must be a ratio of two positive numbers".to_string(),
+            ));
+        }
+        // without a hole card nothing is known of a dealer blackjack until the player is done,
+        // so a surrender can only be the early kind
+        if self.surrender == Surrender::Late && self.hole_card != HoleCardRule::Peek {
+            return Err(RulesError::Invalid(
+                "late surrender needs hole_card = \"peek\", use early surrender without a hole card"
+                    .to_string(),
+            ));
+        }
+        for hand_rule in &self.hand_rules {
+            match hand_rule {
+                HandRule::CardCharlie { cards } if *cards < 3 => {
+                    return Err(RulesError::Invalid(
+                        "card_charlie needs at least 3 cards".to_string(),
+                    ));
+                }
+                HandRule::CardTrick { cards, .. } if *cards < 3 => {
+                    return Err(RulesError::Invalid(
+                        "card_trick needs at least 3 cards".to_string(),
+                    ));
+                }
+                HandRule::CardTrick { payout, .. } if payout.denominator == 0 => {
+                    return Err(RulesError::Invalid(
+                        "card_trick payout must not divide by 0".to_string(),
+                    ));
+                }
+                HandRule::CardCountTwentyOne { cards, .. } if *cards < 3 => {
+                    return Err(RulesError::Invalid(
+                        "card_count_twenty_one needs at least 3 cards".to_string(),
+                    ));
+                }
+                HandRule::CardCountTwentyOne { payout, .. } if payout.denominator == 0 => {
+                    return Err(RulesError::Invalid(
+                        "card_count_twenty_one payout must not divide by 0".to_string(),
+                    ));
+                }
+                HandRule::ThreeCardTwentyOne {
+                    mixed,
+                    suited,
+                    spades,
+                    ..
+                } if [mixed, suited, spades]
+                    .iter()
+                    .any(|payout| payout.denominator == 0) =>
+                {
+                    return Err(RulesError::Invalid(
+                        "three_card_twenty_one payouts must not divide by 0".to_string(),
+                    ));
+                }
+                HandRule::ThreeCardTwentyOne { ranks, .. }
+                    if Hand::from_cards(
+                        ranks.map(|rank| Card::new(rank, Suit::Spades)).to_vec(),
+                    )
+                    .soft_total()
+                        != 21 =>
+                {
+                    return Err(RulesError::Invalid(
+                        "three_card_twenty_one ranks must add up to 21".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        for side_bet_rule in &self.side_bets {
+            if side_bet_rule
+                .payouts()
+                .iter()
+                .any(|payout| payout.denominator == 0)
+            {
+                return Err(RulesError::Invalid(format!(
+                    "{} payouts must not divide by 0",
+                    side_bet_rule.side_bet().name()
+                )));
+            }
+        }
+        if self.max_hands_per_seat == 0 {
+            return Err(RulesError::Invalid(
+                "max_hands_per_seat must be at least 1".to_string(),
+            ));
+        }
+        if self.starting_balance < Chips::new(1) {
+            return Err(RulesError::Invalid(
+                "starting_balance must be at least 1".to_string(),
+            ));
+        }
+        if self.min_bet < Chips::new(1) {
+            return Err(RulesError::Invalid(
+                "min_bet must be at least 1".to_string(),
+            ));
+        }
+        if let Some(max_bet) = self.max_bet {
+            if max_bet < self.min_bet {
+                return Err(RulesError::Invalid(
+                    "max_bet must not be less than min_bet".to_string(),
+                ));
+            }
+        }
+        if let Some(bet_increment) = self.bet_increment {
+            if bet_increment <= Chips::ZERO {
+                return Err(RulesError::Invalid(
+                    "bet_increment must be more than 0".to_string(),
+                ));
+            }
+            let is_limit_off_increment = !self.min_bet.is_multiple_of(bet_increment)
+                || self
+                    .max_bet
+                    .is_some_and(|max_bet| !max_bet.is_multiple_of(bet_increment));
+            if is_limit_off_increment {
+                return Err(RulesError::Invalid(
+                    "min_bet and max_bet must be whole numbers of bet_increment".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A payout written the way tables print it, such as `3:2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PayoutRatio {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl PayoutRatio {
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        PayoutRatio {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// What a winning `bet` is paid, with any remainder rounded by `rounding`.
+    pub fn apply(&self, bet: Chips, rounding: PayoutRounding) -> Chips {
+        bet.scale(self.numerator, self.denominator, rounding)
+    }
+}
+
+impl fmt::Display for PayoutRatio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+impl TryFrom<String> for PayoutRatio {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid payout ratio '{}', expected a form like 3:2", value);
+
+        let (numerator, denominator) = value.split_once(':').ok_or_else(invalid)?;
+        let numerator = numerator.trim().parse::<u32>().map_err(|_| invalid())?;
+        let denominator = denominator.trim().parse::<u32>().map_err(|_| invalid())?;
+
+        Ok(PayoutRatio::new(numerator, denominator))
+    }
+}
+
+impl From<PayoutRatio> for String {
+    fn from(value: PayoutRatio) -> Self {
+        value.to_string()
+    }
+}
+
+#[test]
+fn test_house_rules_parse_partial_files() {
+    let rules: HouseRules = toml::from_str(
+        r#"
+        deck_count = 2
+        dealer_hits_soft_17 = true
+        blackjack_payout = "6:5"
+        payout_rounding = "down"
+        min_bet = 5
+
+        [[hand_rules]]
+        rule = "card_charlie"
+        cards = 6
+        "#,
+    )
+    .unwrap();
+    assert_eq!(rules.deck_count, 2);
+    assert!(rules.dealer_hits_soft_17);
+    assert_eq!(rules.blackjack_payout, PayoutRatio::new(6, 5));
+    assert_eq!(rules.payout_rounding, PayoutRounding::Down);
+    assert_eq!(rules.hand_rules, vec![HandRule::CardCharlie { cards: 6 }]);
+    assert_eq!(rules.min_bet, Chips::new(5));
+    assert_eq!(
+        rules.starting_balance,
+        HouseRules::default().starting_balance
+    );
+    assert!(rules.validate().is_ok());
+
+    let rules: HouseRules =
+        serde_json::from_str(r#"{ "deck_count": 3, "surrender": "late" }"#).unwrap();
+    assert_eq!(rules.surrender, Surrender::Late);
+    assert!(rules.validate().is_err());
+
+    assert!(serde_json::from_str::<HouseRules>(r#"{ "dealer_hits_17": true }"#).is_err());
+}
+
+#[test]
+fn test_blackjack_payout_ratios() {
+    assert!(serde_json::from_str::<HouseRules>(r#"{ "blackjack_payout": "3-2" }"#).is_err());
+    assert!(
+        serde_json::from_str::<HouseRules>(r#"{ "blackjack_payout": "0:1" }"#)
+            .unwrap()
+            .validate()
+            .is_err()
+    );
+
+    // the common tables pay exactly, on bets that do not divide evenly too
+    let bet = Chips::new(15);
+    let payouts: Vec<String> = ["3:2", "6:5", "7:5", "1:1"]
+        .iter()
+        .map(|ratio| {
+            PayoutRatio::try_from(ratio.to_string())
+                .unwrap()
+                .apply(bet, PayoutRounding::HalfChip)
+                .to_string()
+        })
+        .collect();
+    assert_eq!(payouts, vec!["22.5", "18", "21", "15"]);
+}
+
+#[test]
+fn test_rules_file_over_a_variant() {
+    // every preset is a valid table
+    for variant in GameVariant::ALL {
+        assert!(HouseRules::for_variant(variant).validate().is_ok());
+    }
+
+    // a rules file changes a variant's rules rather than the defaults
+    let spanish_21 = HouseRules::for_variant(GameVariant::Spanish21);
+    let path = std::env::temp_dir().join("blackjack_test_variant_rules.toml");
+    std::fs::write(&path, "deck_count = 8\nsurrender = \"none\"\n").unwrap();
+    let rules = HouseRules::load_from_file_over(&spanish_21, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rules.deck_count, 8);
+    assert_eq!(rules.surrender, Surrender::None);
+    assert_eq!(rules.removed_ranks, vec![Rank::Ten]);
+    assert_eq!(rules.hand_rules, spanish_21.hand_rules);
+}
+
+#[test]
+fn test_table_bet_limits() {
+    // bets keep to the table minimum, maximum and increment
+    let limited_rules = HouseRules {
+        min_bet: Chips::new(10),
+        max_bet: Some(Chips::new(100)),
+        bet_increment: Some(Chips::new(5)),
+        ..HouseRules::default()
+    };
+    assert!(limited_rules.validate().is_ok());
+    assert!(limited_rules.is_bet_allowed(Chips::new(500), Chips::new(25)));
+    assert!(!limited_rules.is_bet_allowed(Chips::new(500), Chips::new(27)));
+    assert!(!limited_rules.is_bet_allowed(Chips::new(500), Chips::new(105)));
+    assert!(!limited_rules.is_bet_allowed(Chips::new(20), Chips::new(25)));
+    assert!(!limited_rules.is_wager_allowed(Chips::new(5)));
+
+    let off_increment_rules = HouseRules {
+        min_bet: Chips::new(7),
+        ..limited_rules.clone()
+    };
+    assert!(off_increment_rules.validate().is_err());
+    let zero_increment_rules = HouseRules {
+        bet_increment: Some(Chips::ZERO),
+        ..limited_rules
+    };
+    assert!(zero_increment_rules.validate().is_err());
+}
+
+#[test]
+fn test_hand_rule_validation() {
+    let rules_with = |hand_rule: HandRule| HouseRules {
+        hand_rules: vec![hand_rule],
+        ..HouseRules::default()
+    };
+
+    assert!(rules_with(HandRule::CardCharlie { cards: 5 })
+        .validate()
+        .is_ok());
+    assert!(rules_with(HandRule::CardCharlie { cards: 2 })
+        .validate()
+        .is_err());
+    assert!(rules_with(HandRule::CardTrick {
+        cards: 5,
+        payout: PayoutRatio::new(2, 1)
+    })
+    .validate()
+    .is_ok());
+    // a ratio that divides by 0 would panic when the hand is paid
+    assert!(rules_with(HandRule::CardTrick {
+        cards: 5,
+        payout: PayoutRatio::new(1, 0)
+    })
+    .validate()
+    .is_err());
+    assert!(rules_with(HandRule::CardCountTwentyOne {
+        cards: 5,
+        payout: PayoutRatio::new(3, 0)
+    })
+    .validate()
+    .is_err());
+    assert!(rules_with(HandRule::ThreeCardTwentyOne {
+        ranks: [Rank::Seven, Rank::Seven, Rank::Seven],
+        mixed: PayoutRatio::new(3, 2),
+        suited: PayoutRatio::new(2, 0),
+        spades: PayoutRatio::new(3, 1),
+    })
+    .validate()
+    .is_err());
+}
@@ -0,0 +1,281 @@
+use crate::chips::Chips;
+use crate::enums::{PayoutRounding, Rank};
+use crate::models::Card;
+use crate::rules::PayoutRatio;
+
+use serde::{Deserialize, Serialize};
+
+/// A wager settled on the player's first two cards and the dealer upcard, apart from the main bet.
+pub trait SideBet {
+    /// The name the side bet is offered, shown and recorded under.
+    fn name(&self) -> &'static str;
+
+    /// The hand the cards make and what it pays, or `None` when the side bet loses.
+    fn evaluate(
+        &self,
+        player_cards: [Card; 2],
+        dealer_upcard: Card,
+    ) -> Option<(&'static str, PayoutRatio)>;
+}
+
+/// A side bet the table offers, with the paytable it pays from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "bet", rename_all = "snake_case")]
+pub enum SideBetRule {
+    TwentyOnePlusThree(TwentyOnePlusThree),
+    PerfectPairs(PerfectPairs),
+}
+
+impl SideBetRule {
+    pub fn side_bet(&self) -> &dyn SideBet {
+        match self {
+            SideBetRule::TwentyOnePlusThree(side_bet) => side_bet,
+            SideBetRule::PerfectPairs(side_bet) => side_bet,
+        }
+    }
+
+    /// Every ratio on the side bet's paytable.
+    pub fn payouts(&self) -> Vec<PayoutRatio> {
+        match self {
+            SideBetRule::TwentyOnePlusThree(paytable) => vec![
+                paytable.flush,
+                paytable.straight,
+                paytable.three_of_a_kind,
+                paytable.straight_flush,
+                paytable.suited_three_of_a_kind,
+            ],
+            SideBetRule::PerfectPairs(paytable) => {
+                vec![paytable.mixed, paytable.colored, paytable.perfect]
+            }
+        }
+    }
+}
+
+/// How a placed side bet was settled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SideBetResult {
+    pub name: String,
+    pub bet: Chips,
+    // the paying hand, if the bet won
+    pub outcome: Option<String>,
+    pub payout: Chips,
+}
+
+/// Settles `bet` on `side_bet` for the opening cards, rounding any payout by `rounding`.
+pub fn settle_side_bet(
+    side_bet: &dyn SideBet,
+    bet: Chips,
+    player_cards: [Card; 2],
+    dealer_upcard: Card,
+    rounding: PayoutRounding,
+) -> SideBetResult {
+    let outcome = side_bet.evaluate(player_cards, dealer_upcard);
+    SideBetResult {
+        name: side_bet.name().to_string(),
+        bet,
+        outcome: outcome.map(|(outcome, _)| outcome.to_string()),
+        payout: match outcome {
+            Some((_, payout)) => payout.apply(bet, rounding),
+            None => -bet,
+        },
+    }
+}
+
+/// 21+3: the poker hand the player's two cards make with the dealer upcard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TwentyOnePlusThree {
+    pub flush: PayoutRatio,
+    pub straight: PayoutRatio,
+    pub three_of_a_kind: PayoutRatio,
+    pub straight_flush: PayoutRatio,
+    pub suited_three_of_a_kind: PayoutRatio,
+}
+
+impl Default for TwentyOnePlusThree {
+    fn default() -> Self {
+        TwentyOnePlusThree {
+            flush: PayoutRatio::new(5, 1),
+            straight: PayoutRatio::new(10, 1),
+            three_of_a_kind: PayoutRatio::new(30, 1),
+            straight_flush: PayoutRatio::new(40, 1),
+            suited_three_of_a_kind: PayoutRatio::new(100, 1),
+        }
+    }
+}
+
+impl SideBet for TwentyOnePlusThree {
+    fn name(&self) -> &'static str {
+        "21+3"
+    }
+
+    fn evaluate(
+        &self,
+        player_cards: [Card; 2],
+        dealer_upcard: Card,
+    ) -> Option<(&'static str, PayoutRatio)> {
+        let cards = [player_cards[0], player_cards[1], dealer_upcard];
+        let is_flush = cards.iter().all(|card| card.suit == cards[0].suit);
+        let is_three_of_a_kind = cards.iter().all(|card| card.rank == cards[0].rank);
+
+        // aces play high or low, so Q K A and A 2 3 are both straights
+        let mut positions = cards.map(|card| rank_position(card.rank));
+        positions.sort();
+        let is_straight = (positions[1] == positions[0] + 1 && positions[2] == positions[1] + 1)
+            || positions == [0, 11, 12];
+
+        if is_three_of_a_kind && is_flush {
+            Some(("suited three of a kind", self.suited_three_of_a_kind))
+        } else if is_straight && is_flush {
+            Some(("straight flush", self.straight_flush))
+        } else if is_three_of_a_kind {
+            Some(("three of a kind", self.three_of_a_kind))
+        } else if is_straight {
+            Some(("straight", self.straight))
+        } else if is_flush {
+            Some(("flush", self.flush))
+        } else {
+            None
+        }
+    }
+}
+
+/// Perfect Pairs: whether the player's first two cards are a pair, paying more the
+/// closer the suits match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfectPairs {
+    pub mixed: PayoutRatio,
+    pub colored: PayoutRatio,
+    pub perfect: PayoutRatio,
+}
+
+impl Default for PerfectPairs {
+    fn default() -> Self {
+        PerfectPairs {
+            mixed: PayoutRatio::new(6, 1),
+            colored: PayoutRatio::new(12, 1),
+            perfect: PayoutRatio::new(25, 1),
+        }
+    }
+}
+
+impl SideBet for PerfectPairs {
+    fn name(&self) -> &'static str {
+        "Perfect Pairs"
+    }
+
+    fn evaluate(
+        &self,
+        player_cards: [Card; 2],
+        _dealer_upcard: Card,
+    ) -> Option<(&'static str, PayoutRatio)> {
+        let [first, second] = player_cards;
+        if first.rank != second.rank {
+            None
+        } else if first.suit == second.suit {
+            Some(("perfect pair", self.perfect))
+        } else if first.suit.is_red() == second.suit.is_red() {
+            Some(("colored pair", self.colored))
+        } else {
+            Some(("mixed pair", self.mixed))
+        }
+    }
+}
+
+// where the rank falls from ace to king, so that neighbouring ranks are one apart
+fn rank_position(rank: Rank) -> usize {
+    Rank::ALL
+        .iter()
+        .position(|&other_rank| other_rank == rank)
+        .expect("Error: rank missing from Rank::ALL.")
+}
+
+#[test]
+fn test_side_bet_paytables() {
+    use crate::enums::Suit;
+    use crate::rules::HouseRules;
+
+    let card = |rank: Rank, suit: Suit| Card::new(rank, suit);
+    let twenty_one_plus_three = TwentyOnePlusThree::default();
+    let evaluate = |first: Card, second: Card, upcard: Card| {
+        twenty_one_plus_three
+            .evaluate([first, second], upcard)
+            .map(|(outcome, _)| outcome)
+    };
+
+    assert_eq!(
+        evaluate(
+            card(Rank::Queen, Suit::Hearts),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Ace, Suit::Spades)
+        ),
+        Some("straight")
+    );
+    assert_eq!(
+        evaluate(
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Three, Suit::Hearts)
+        ),
+        Some("straight flush")
+    );
+    assert_eq!(
+        evaluate(
+            card(Rank::King, Suit::Hearts),
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::Two, Suit::Hearts)
+        ),
+        Some("flush")
+    );
+    assert_eq!(
+        evaluate(
+            card(Rank::Seven, Suit::Clubs),
+            card(Rank::Seven, Suit::Clubs),
+            card(Rank::Seven, Suit::Clubs)
+        ),
+        Some("suited three of a kind")
+    );
+    // a ten and a jack are different ranks though they count the same
+    assert_eq!(
+        evaluate(
+            card(Rank::Ten, Suit::Clubs),
+            card(Rank::Jack, Suit::Hearts),
+            card(Rank::Ten, Suit::Spades)
+        ),
+        None
+    );
+
+    let perfect_pairs = PerfectPairs::default();
+    let pair_of = |first: Suit, second: Suit| {
+        settle_side_bet(
+            &perfect_pairs,
+            Chips::new(5),
+            [card(Rank::Eight, first), card(Rank::Eight, second)],
+            card(Rank::Two, Suit::Clubs),
+            PayoutRounding::HalfChip,
+        )
+    };
+    assert_eq!(pair_of(Suit::Hearts, Suit::Hearts).payout, Chips::new(125));
+    assert_eq!(pair_of(Suit::Hearts, Suit::Diamonds).payout, Chips::new(60));
+    let mixed_pair = pair_of(Suit::Hearts, Suit::Spades);
+    assert_eq!(mixed_pair.outcome.as_deref(), Some("mixed pair"));
+    assert_eq!(mixed_pair.payout, Chips::new(30));
+
+    // paytables read from the rules, filling in anything left out
+    let side_bet: SideBetRule =
+        toml::from_str("bet = \"perfect_pairs\"\nperfect = \"30:1\"").unwrap();
+    assert_eq!(
+        side_bet,
+        SideBetRule::PerfectPairs(PerfectPairs {
+            perfect: PayoutRatio::new(30, 1),
+            ..PerfectPairs::default()
+        })
+    );
+    assert_eq!(side_bet.side_bet().name(), "Perfect Pairs");
+
+    // a paytable that divides by 0 would panic the first time the bet wins
+    let rules: HouseRules =
+        toml::from_str("[[side_bets]]\nbet = \"twenty_one_plus_three\"\nflush = \"1:0\"").unwrap();
+    assert!(rules.validate().is_err());
+}